@@ -1,4 +1,8 @@
-use clap::{Parser, Subcommand};
+use crate::config::Config;
+use crate::level::Level;
+use crate::models::selector::TaskSelector;
+use crate::models::task::TaskStatus;
+use clap::{Args, Parser, Subcommand};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -14,33 +18,32 @@ pub struct Cli {
     pub command: Commands,
 }
 
-#[derive(Debug, Clone, Copy, clap::ValueEnum)]
-pub enum Level {
-    Feature,
-    Fix,
-    Chore,
-    Docs,
-    Refactor,
-    Test,
-    Perf,
-    Style,
-    Ci,
+/// Parse a level name or alias against the built-in and configured levels
+fn parse_level(name: &str) -> Result<Level, String> {
+    let levels = Config::load()
+        .and_then(|config| config.levels())
+        .map_err(|e| e.to_string())?;
+
+    levels
+        .find(name)
+        .cloned()
+        .ok_or_else(|| format!("unknown level (known: {})", levels.names().join(", ")))
 }
 
-impl Level {
-    /// Convert to lowercase string for branch names and paths
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            Level::Feature => "feature",
-            Level::Fix => "fix",
-            Level::Chore => "chore",
-            Level::Docs => "docs",
-            Level::Refactor => "refactor",
-            Level::Test => "test",
-            Level::Perf => "perf",
-            Level::Style => "style",
-            Level::Ci => "ci",
-        }
+/// Task argument shared by commands acting on a single task
+#[derive(Args, Debug)]
+pub struct TaskArgs {
+    /// Task: reference ID, title, project/title, or a unique prefix or substring of them;
+    /// the project name when TITLE is given too. Defaults to the task of the current directory
+    pub task: Option<String>,
+
+    /// Task title, or a unique prefix or substring of it, within the project given as TASK
+    pub title: Option<String>,
+}
+
+impl TaskArgs {
+    pub fn selector(self) -> TaskSelector {
+        TaskSelector::new(self.task, self.title)
     }
 }
 
@@ -51,12 +54,12 @@ pub enum Commands {
         /// Project name
         project: String,
 
-        /// Path to main repository (e.g., ~/projects/myapp/main)
-        main_repo_path: PathBuf,
+        /// Path to main repository (e.g., ~/projects/myapp/main), defaults to the project config
+        main_repo_path: Option<PathBuf>,
 
-        /// Task level (feature, fix, chore, etc.)
-        #[arg(short, long, value_enum)]
-        level: Level,
+        /// Task level (feature, fix, chore, etc. or one from the config), defaults to review with --from-branch, otherwise the project config or feature
+        #[arg(short, long, value_parser = parse_level)]
+        level: Option<Level>,
 
         /// Task ID/reference (e.g., JIRA-123)
         #[arg(short, long)]
@@ -70,6 +73,18 @@ pub enum Commands {
         #[arg(short, long)]
         description: Option<String>,
 
+        /// Base to branch from: local branch, remote branch (origin/main), tag or SHA
+        #[arg(short, long = "base", visible_alias = "base-branch")]
+        base: Option<String>,
+
+        /// Check out an existing local or remote branch instead of creating one
+        #[arg(long, conflicts_with = "base")]
+        from_branch: Option<String>,
+
+        /// Fetch the base's remote before creating the worktree
+        #[arg(long)]
+        fetch: bool,
+
         /// Remote URL for future integration
         #[arg(long)]
         remote_url: Option<String>,
@@ -81,22 +96,79 @@ pub enum Commands {
 
     /// List tasks
     List {
-        /// Filter by project name
+        /// Filter by project name; * and ? match any characters
         #[arg(short, long)]
         project: Option<String>,
 
+        /// Filter by task status
+        #[arg(short, long, value_enum)]
+        state: Option<TaskStatus>,
+
+        /// Filter by level
+        #[arg(short, long, value_parser = parse_level)]
+        level: Option<Level>,
+
+        /// Filter by reference ID, ignoring case; * and ? match any characters
+        #[arg(short, long)]
+        reference: Option<String>,
+
+        /// Only tasks whose worktree is inside this directory
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// Only tasks whose worktree has uncommitted changes
+        #[arg(short, long, conflicts_with = "archived")]
+        dirty: bool,
+
+        /// Show live worktree state (branch, dirty, ahead/behind, missing)
+        #[arg(long)]
+        status: bool,
+
         /// Output format
         #[arg(short, long, value_enum, default_value = "table")]
         format: OutputFormat,
+
+        /// List archived tasks instead of active ones
+        #[arg(short, long, conflicts_with = "status")]
+        archived: bool,
+
+        /// Sort order; by default tasks are ordered by project, then by creation
+        #[arg(long, value_enum)]
+        sort: Option<SortKey>,
+
+        /// Comma-separated columns of the table, e.g. "title,branch,worktree"
+        #[arg(
+            short,
+            long,
+            value_enum,
+            value_delimiter = ',',
+            conflicts_with = "archived"
+        )]
+        columns: Vec<Column>,
+    },
+
+    /// Show the history of a task: when it was created, switched to, changed and removed
+    Log {
+        #[command(flatten)]
+        task: TaskArgs,
+
+        /// Show the history of all tasks, also inside a task's worktree
+        #[arg(short, long, conflicts_with = "task")]
+        all: bool,
+
+        /// Show only the last N events
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+
+        /// Print events as JSON
+        #[arg(short, long)]
+        json: bool,
     },
 
     /// Remove a task
     Remove {
-        /// Project name
-        project: String,
-
-        /// Task title
-        title: String,
+        #[command(flatten)]
+        task: TaskArgs,
 
         /// Also remove the git worktree
         #[arg(short = 'w', long)]
@@ -107,13 +179,311 @@ pub enum Commands {
         force: bool,
     },
 
-    /// Switch to a task (outputs worktree path for shell integration)
-    Switch {
+    /// Archive a finished task, keeping its record, branch and final commit
+    #[command(visible_alias = "done")]
+    Archive {
+        #[command(flatten)]
+        task: TaskArgs,
+
+        /// Also remove the git worktree; the branch is kept
+        #[arg(short = 'w', long)]
+        remove_worktree: bool,
+
+        /// Force removal even if worktree has uncommitted changes
+        #[arg(short, long, requires = "remove_worktree")]
+        force: bool,
+    },
+
+    /// Restore an archived task, recreating its worktree from the kept branch
+    Unarchive {
+        #[command(flatten)]
+        task: TaskArgs,
+    },
+
+    /// Show everything about a task: metadata, branch, upstream, last commit and changes
+    Show {
+        #[command(flatten)]
+        task: TaskArgs,
+
+        /// Print the details as JSON
+        #[arg(short, long)]
+        json: bool,
+    },
+
+    /// Print the task whose worktree contains the current directory
+    Current {
+        /// Print the task as JSON
+        #[arg(short, long, conflicts_with = "short")]
+        json: bool,
+
+        /// Print only the reference, or the title if the task has none
+        #[arg(short, long)]
+        short: bool,
+
+        /// Exit with an error status but print nothing when not inside a task
+        #[arg(short, long)]
+        quiet: bool,
+    },
+
+    /// Print shell integration: a `tm` function that changes directory on switch,
+    /// a prompt segment showing the current task, and completions
+    Init {
+        /// Shell to generate the script for
+        #[arg(value_enum)]
+        shell: Shell,
+
+        /// Leave the prompt unchanged
+        #[arg(long)]
+        no_prompt: bool,
+    },
+
+    /// Print a completion script that completes project names and task titles
+    Completions {
+        /// Shell to generate the script for
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+
+    /// Print completion candidates for the words after `tm` (used by completion scripts)
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        words: Vec<String>,
+    },
+
+    /// Check stored tasks against git and the filesystem
+    Doctor {
+        /// Repair the problems found
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Remove tasks whose branch was merged or that saw no activity for a while,
+    /// with their worktrees and merged branches, after confirmation
+    Prune {
+        /// Only consider tasks of this project
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Days without commits, switches or changes after which a task is stale; 0 disables
+        #[arg(long, default_value_t = 30)]
+        stale_days: u32,
+
+        /// Also prune tasks whose upstream branch was deleted on the remote
+        #[arg(long)]
+        gone: bool,
+
+        /// Remove without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Show what would be pruned without removing anything
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+
+        /// Remove worktrees even if they have uncommitted changes
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Import existing worktrees of a repository as tasks
+    Import {
+        /// Project name
+        project: String,
+
+        /// Path to main repository (e.g., ~/projects/myapp/main)
+        main_repo_path: PathBuf,
+
+        /// Show what would be imported without saving anything
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+
+    /// Check out an existing branch (e.g. a colleague's PR) as a review task
+    Review {
         /// Project name
         project: String,
 
-        /// Task title
+        /// Branch to review, local or on a remote (e.g. feature/JIRA-123-auth)
+        branch: String,
+
+        /// Path to main repository (e.g., ~/projects/myapp/main), defaults to the project config
+        #[arg(short, long)]
+        main_repo: Option<PathBuf>,
+
+        /// Task description
+        #[arg(short, long)]
+        description: Option<String>,
+
+        /// Fetch from the remote before looking up the branch
+        #[arg(long)]
+        fetch: bool,
+    },
+
+    /// Change the status of a task
+    Status {
+        /// Task as for `tm switch` (TASK, PROJECT TITLE, or nothing for the current task),
+        /// followed by the new status: todo, in-progress, review or done
+        #[arg(value_names = ["TASK", "STATE"], num_args = 1..=3, required = true)]
+        args: Vec<String>,
+
+        /// Set the status even if the transition is not allowed
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Move a task to another project, leaving its worktree where it is
+    Mv {
+        /// Project the task is in
+        from_project: String,
+
+        /// Task title, reference ID or a unique part of them
         title: String,
+
+        /// Project to move the task to
+        to_project: String,
+    },
+
+    /// Change a task's level, ID or name, renaming its branch and moving its worktree
+    Rename {
+        #[command(flatten)]
+        task: TaskArgs,
+
+        /// New level
+        #[arg(short, long, value_parser = parse_level)]
+        level: Option<Level>,
+
+        /// New ID/reference (e.g., JIRA-123)
+        #[arg(short, long)]
+        id: Option<String>,
+
+        /// New name
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Rename even if the worktree has uncommitted changes
+        #[arg(short, long)]
+        force: bool,
+
+        /// Leave a worktree that isn't where the project's template puts it in place
+        #[arg(long)]
+        keep_worktree: bool,
+    },
+
+    /// Edit a task's description, reference and URLs; opens $EDITOR without options
+    Edit {
+        #[command(flatten)]
+        task: TaskArgs,
+
+        /// New description, empty to clear it
+        #[arg(short, long)]
+        description: Option<String>,
+
+        /// New ID/reference (e.g., JIRA-123), empty to clear it
+        #[arg(short, long)]
+        reference: Option<String>,
+
+        /// New remote URL, empty to clear it
+        #[arg(long)]
+        remote_url: Option<String>,
+
+        /// New API URL, empty to clear it
+        #[arg(long)]
+        api_url: Option<String>,
+    },
+
+    /// Manage per-project configuration
+    #[command(subcommand)]
+    Project(ProjectCommands),
+
+    /// Restore the task file from a backup
+    Restore {
+        /// Backup to restore (1 is the most recent)
+        #[arg(default_value_t = 1)]
+        backup: usize,
+
+        /// List available backups instead of restoring
+        #[arg(short, long)]
+        list: bool,
+    },
+
+    /// Pick a task in a fuzzy finder and output its worktree path, like `tm switch`
+    Pick,
+
+    /// Switch to a task (outputs worktree path for shell integration),
+    /// picking it interactively if no task is given
+    Switch {
+        #[command(flatten)]
+        task: TaskArgs,
+    },
+
+    /// Open a terminal dashboard to browse, switch to, add and remove tasks
+    /// (outputs the worktree path on switch, like `tm switch`)
+    Ui,
+}
+
+#[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)]
+pub enum ProjectCommands {
+    /// Add a project, or update the settings of an existing one
+    Add {
+        /// Project name
+        name: String,
+
+        /// Path to main repository (e.g., ~/projects/myapp/main)
+        main_repo: Option<PathBuf>,
+
+        /// Default base for new task branches
+        #[arg(short, long)]
+        base_branch: Option<String>,
+
+        /// Default task level
+        #[arg(short, long, value_parser = parse_level)]
+        level: Option<Level>,
+
+        /// Directory to create worktrees in (default: parent of the main repository)
+        #[arg(short, long)]
+        worktree_root: Option<PathBuf>,
+
+        /// Branch name template (default: "{level}/{id}-{name:kebab}")
+        #[arg(long)]
+        branch_template: Option<String>,
+
+        /// Worktree path template relative to the root (default: "{level}/{id}-{name:snake}")
+        #[arg(long)]
+        worktree_template: Option<String>,
+
+        /// Command to run in new worktrees after creation (repeatable)
+        #[arg(long)]
+        post_create: Vec<String>,
+
+        /// Command to run in a worktree before it is removed (repeatable)
+        #[arg(long)]
+        pre_remove: Vec<String>,
+    },
+
+    /// List configured projects
+    List,
+
+    /// Show the effective settings of a project
+    Show {
+        /// Project name
+        name: String,
+    },
+
+    /// Rename a project, moving its tasks and configuration to the new name
+    Rename {
+        /// Project name
+        name: String,
+
+        /// New project name
+        new_name: String,
+    },
+
+    /// Remove a project's configuration (tasks are kept)
+    Remove {
+        /// Project name
+        name: String,
     },
 }
 
@@ -126,3 +496,59 @@ pub enum OutputFormat {
     /// JSON format for scripting
     Json,
 }
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum SortKey {
+    /// Project name, then title
+    Project,
+    /// Title, i.e. level, then ID and name
+    Title,
+    /// Status, from todo to done
+    Status,
+    /// Reference ID; tasks without one come last
+    Reference,
+    /// Worktree path
+    Path,
+    /// When the task was created, most recent first
+    Created,
+    /// When the task's metadata last changed, most recent first
+    Updated,
+    /// When `tm switch` last went to the task, most recent first
+    Switched,
+}
+
+/// A column of the `tm list` table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Column {
+    Project,
+    Title,
+    Status,
+    Reference,
+    Description,
+    /// Checked out branch
+    Branch,
+    /// Live worktree state: clean, dirty, ahead/behind or missing
+    Worktree,
+    /// Worktree path
+    Path,
+    Created,
+    Updated,
+    Switched,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+        }
+    }
+}
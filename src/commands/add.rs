@@ -1,12 +1,15 @@
-use crate::cli::Level;
-use crate::config::get_tasks_file_path;
+use crate::config::{get_tasks_file_path, Config};
 use crate::error::{TmError, TmResult};
 use crate::git;
+use crate::hooks::{self, HookContext};
+use crate::level::Level;
+use crate::models::history::{self, EventKind};
 use crate::models::{storage::TaskStorage, task::Task};
+use crate::template::TemplateVars;
 use std::path::PathBuf;
 
 /// Validate input parameters
-fn validate_inputs(id: &str, name: &str) -> TmResult<()> {
+pub fn validate_inputs(id: &str, name: &str) -> TmResult<()> {
     // Check for empty name after conversion
     let name_snake = git::to_snake_case(name);
     if name_snake.is_empty() {
@@ -27,59 +30,168 @@ fn validate_inputs(id: &str, name: &str) -> TmResult<()> {
     Ok(())
 }
 
+/// Side effects of `tm add` that have to be undone if a later step fails
+struct Rollback {
+    /// Parent directory of the worktree, if it was created by this run
+    created_dir: Option<PathBuf>,
+    /// Worktree created by this run
+    worktree_path: Option<PathBuf>,
+    /// Branch created by this run, with the repository it lives in
+    branch: Option<(PathBuf, String)>,
+}
+
+impl Rollback {
+    fn new() -> Self {
+        Self {
+            created_dir: None,
+            worktree_path: None,
+            branch: None,
+        }
+    }
+
+    /// Undo the recorded side effects in reverse order, reporting what was undone
+    fn undo(self) {
+        if let Some(path) = self.worktree_path {
+            match git::remove_worktree(&path, true) {
+                Ok(()) => eprintln!("Rolled back: removed worktree at {}", path.display()),
+                Err(e) => eprintln!("Could not remove worktree at {}: {}", path.display(), e),
+            }
+        }
+
+        if let Some((main_repo_path, branch_name)) = self.branch {
+            match git::delete_branch(&main_repo_path, &branch_name) {
+                Ok(()) => eprintln!("Rolled back: deleted branch {}", branch_name),
+                Err(e) => eprintln!("Could not delete branch {}: {}", branch_name, e),
+            }
+        }
+
+        // Only removes the directory if nothing else was put there in the meantime
+        if let Some(dir) = self.created_dir {
+            if std::fs::remove_dir(&dir).is_ok() {
+                eprintln!("Rolled back: removed directory {}", dir.display());
+            }
+        }
+    }
+}
+
+/// Where a task's branch comes from
+pub enum BranchSource {
+    /// Create a new branch from a base (HEAD if None)
+    New { name: String, base: Option<String> },
+    /// Check out an existing local branch, or create one tracking the remote branch
+    Existing(String),
+}
+
+impl BranchSource {
+    fn name(&self) -> &str {
+        match self {
+            BranchSource::New { name, .. } => name,
+            BranchSource::Existing(name) => name,
+        }
+    }
+}
+
+/// Everything needed to create a task's worktree and register the task
+pub struct NewTask {
+    pub project: String,
+    pub main_repo_path: PathBuf,
+    pub branch: BranchSource,
+    /// Fetch the remote before creating the worktree
+    pub fetch: bool,
+    /// Commands to run in the worktree once it is created
+    pub post_create: Vec<String>,
+    /// Task to save, with title, worktree path and metadata filled in
+    pub task: Task,
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn execute(
     project: String,
-    main_repo_path: PathBuf,
-    level: Level,
+    main_repo_path: Option<PathBuf>,
+    level: Option<Level>,
     id: String,
     name: String,
     description: Option<String>,
+    base: Option<String>,
+    from_branch: Option<String>,
+    fetch: bool,
     remote_url: Option<String>,
     api_url: Option<String>,
 ) -> TmResult<()> {
     // Step 1: Validate inputs
     validate_inputs(&id, &name)?;
 
-    // Step 2: Validate main repo path is a git repository
-    git::validate_worktree(&main_repo_path)?;
-
-    // Step 3: Compute worktree path
-    let level_str = level.as_str();
-    let worktree_path = git::compute_worktree_path(&main_repo_path, level_str, &id, &name)?;
-
-    // Step 4: Check if worktree already exists
-    if worktree_path.exists() {
-        return Err(TmError::WorktreeAlreadyExists {
-            path: worktree_path,
-        });
-    }
+    // Step 2: Fill in unset options from the project configuration
+    let config = Config::load()?;
+    let settings = config.settings(&project, main_repo_path)?;
+    let main_repo_path = settings.main_repo;
+    // Checking out an existing branch is usually reviewing someone else's work
+    let level = match level {
+        Some(level) => level,
+        None if from_branch.is_some() => config.levels()?.review().clone(),
+        None => match settings.level {
+            Some(level) => level,
+            None => config.levels()?.default_level().clone(),
+        },
+    };
+    let base = base.or(settings.base_branch);
 
-    // Step 5: Ensure parent directory exists
-    if let Some(parent) = worktree_path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-
-    // Step 6: Generate branch name
-    let branch_name = git::generate_branch_name(level_str, &id, &name);
+    // Step 3: Validate main repo path is a git repository
+    git::validate_worktree(&main_repo_path)?;
 
-    // Step 7: Create worktree (always, this is now default behavior)
-    git::create_worktree(&main_repo_path, &worktree_path, &branch_name, None)?;
+    // Step 4: Generate task title from components
+    // Format: "{level}/{id}-{name_kebab_case}", independent of the branch template
+    // so titles stay stable identifiers
+    let task_title = git::generate_branch_name(level.as_str(), &id, &name);
 
-    println!("Created worktree at: {}", worktree_path.display());
-    println!("Branch: {}", branch_name);
+    // Step 5: Compute worktree path and branch name from the project's templates,
+    // with {level} standing for the level's directory and branch prefix respectively
+    let vars = TemplateVars {
+        user: &settings.user,
+        project: &project,
+        level: level.dir(),
+        id: &id,
+        name: &name,
+    };
+    let worktree_path = settings
+        .worktree_root
+        .join(settings.worktree_template.render(&vars));
+    let branch_name = settings.branch_template.render(&TemplateVars {
+        level: level.prefix(),
+        ..vars
+    });
 
-    // Step 8: Generate task title from components
-    // Format: "{level}/{id}-{name_kebab_case}" for uniqueness and consistency with branch name
-    let name_kebab = git::to_kebab_case(&name);
-    let task_title = format!("{}/{}-{}", level_str, id, name_kebab);
+    // Step 6: Use the generated branch name, unless an existing branch is checked out
+    let (branch, base) = match from_branch {
+        Some(existing) => (BranchSource::Existing(existing), None),
+        None => {
+            if !git::is_valid_branch_name(&branch_name) {
+                return Err(TmError::InvalidInput {
+                    field: "id/name".to_string(),
+                    reason: format!("'{}' is not a valid branch name", branch_name),
+                });
+            }
+            (
+                BranchSource::New {
+                    name: branch_name,
+                    base: base.clone(),
+                },
+                base,
+            )
+        }
+    };
 
-    // Step 9: Create task with builder pattern
-    let mut task = Task::new(task_title.clone(), worktree_path.clone()).with_reference(id.clone());
+    // Step 7: Create task with builder pattern
+    let mut task = Task::new(task_title, worktree_path)
+        .with_main_repo_path(main_repo_path.clone())
+        .with_reference(id);
 
     if let Some(desc) = description {
         task = task.with_description(desc);
     }
+    if let Some(base) = base {
+        task = task.with_base_branch(base);
+    }
     if let Some(url) = remote_url {
         task = task.with_remote_url(url);
     }
@@ -87,13 +199,173 @@ pub fn execute(
         task = task.with_api_url(url);
     }
 
-    // Step 10: Load storage and add task
-    let tasks_file = get_tasks_file_path()?;
-    let mut storage = TaskStorage::load(&tasks_file)?;
-    storage.add_task(project.clone(), task)?;
-    storage.save(&tasks_file)?;
+    create(NewTask {
+        project,
+        main_repo_path,
+        branch,
+        fetch,
+        post_create: settings.hooks.post_create,
+        task,
+    })
+}
+
+/// Create the worktree for a new task and save it, as one transaction
+pub fn create(new_task: NewTask) -> TmResult<()> {
+    create_in(new_task, &get_tasks_file_path()?)
+}
+
+fn create_in(new_task: NewTask, tasks_file: &PathBuf) -> TmResult<()> {
+    let NewTask {
+        project,
+        main_repo_path,
+        branch,
+        fetch,
+        post_create,
+        mut task,
+    } = new_task;
+
+    let task_title = task.title.clone();
+    let worktree_path = task.worktree_path.clone();
+
+    // Step 1: Optionally fetch so remote bases and branches are up to date,
+    // before taking the lock so other tm commands aren't blocked on the network
+    if fetch {
+        let base = match &branch {
+            BranchSource::New { base, .. } => base.as_deref(),
+            BranchSource::Existing(_) => None,
+        };
+        let remote = git::remote_for_base(&main_repo_path, base)?;
+        println!("Fetching {}...", remote);
+        git::fetch_remote(&main_repo_path, &remote)?;
+    }
+
+    // Step 2: Load storage and reject duplicates before touching git
+    let (mut storage, lock) = TaskStorage::load_locked(tasks_file)?;
+    if storage.get_task(&project, &task_title).is_ok() {
+        return Err(TmError::DuplicateTask {
+            project,
+            title: task_title,
+        });
+    }
+    if storage.get_archived(&project, &task_title).is_ok() {
+        return Err(TmError::DuplicateArchivedTask {
+            project,
+            title: task_title,
+        });
+    }
+
+    // Step 3: Check if worktree already exists
+    if worktree_path.exists() {
+        return Err(TmError::WorktreeAlreadyExists {
+            path: worktree_path,
+        });
+    }
+
+    // Step 4: Create the worktree and save the task as one transaction,
+    // undoing every side effect if any step fails
+    let mut rollback = Rollback::new();
+    let result = (|| -> TmResult<()> {
+        // Ensure parent directory exists
+        if let Some(parent) = worktree_path.parent() {
+            if !parent.exists() {
+                std::fs::create_dir_all(parent)?;
+                rollback.created_dir = Some(parent.to_path_buf());
+            }
+        }
+
+        let created_branch = match &branch {
+            BranchSource::New { name, base } => {
+                git::create_worktree(&main_repo_path, &worktree_path, name, base.as_deref())?;
+                true
+            }
+            BranchSource::Existing(name) => {
+                git::checkout_worktree(&main_repo_path, &worktree_path, name)?
+            }
+        };
+        rollback.worktree_path = Some(worktree_path.clone());
+        if created_branch {
+            rollback.branch = Some((main_repo_path.clone(), branch.name().to_string()));
+        }
+
+        println!("Created worktree at: {}", worktree_path.display());
+        println!("Branch: {}", branch.name());
+        if let BranchSource::New {
+            base: Some(base), ..
+        } = &branch
+        {
+            println!("Based on: {}", base);
+        }
+
+        task.start_commit =
+            git::fork_point(&main_repo_path, branch.name(), task.base_branch.as_deref());
+        storage.add_task(project.clone(), task)?;
+        storage.save(tasks_file)?;
+
+        Ok(())
+    })();
+
+    if let Err(e) = result {
+        rollback.undo();
+        return Err(e);
+    }
+    // The task is saved; hooks may run tm themselves
+    drop(lock);
 
     println!("Added task '{}' to project '{}'", task_title, project);
+    history::record(&project, &task_title, EventKind::Created);
+
+    // Step 5: Run post-create hooks; a failing hook leaves the task in place
+    let ctx = HookContext {
+        project: &project,
+        title: &task_title,
+        worktree_path: &worktree_path,
+        main_repo_path: Some(&main_repo_path),
+    };
+    if let Err(e) = hooks::run(&post_create, &ctx) {
+        eprintln!("Warning: {}", e);
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_save_rolls_back_worktree_and_branch() {
+        let (root, repo) = git::test_repo("add");
+        let main = root.join("main");
+
+        // A directory where the temporary file goes makes saving fail after loading works
+        let tasks_file = root.join("config/tasks.toml");
+        std::fs::create_dir_all(root.join("config/tasks.toml.tmp")).unwrap();
+
+        let title = "feature/JIRA-1-auth";
+        let worktree_path = root.join("feature/JIRA-1-auth");
+        let result = create_in(
+            NewTask {
+                project: "proj".to_string(),
+                main_repo_path: main.clone(),
+                branch: BranchSource::New {
+                    name: title.to_string(),
+                    base: None,
+                },
+                fetch: false,
+                post_create: Vec::new(),
+                task: Task::new(title.to_string(), worktree_path.clone()),
+            },
+            &tasks_file,
+        );
+
+        assert!(matches!(result, Err(TmError::Io(_))));
+        assert!(!worktree_path.exists());
+        assert!(!root.join("feature").exists());
+        assert!(repo.worktrees().unwrap().is_empty());
+        assert!(!main.join(".git/worktrees/JIRA-1-auth").exists());
+        assert!(repo.find_branch(title, git2::BranchType::Local).is_err());
+        assert!(!tasks_file.exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
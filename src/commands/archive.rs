@@ -0,0 +1,125 @@
+use crate::config::{get_tasks_file_path, Config};
+use crate::error::{TmError, TmResult};
+use crate::format::short_commit;
+use crate::git;
+use crate::hooks;
+use crate::models::history::{self, EventKind};
+use crate::models::selector::TaskSelector;
+use crate::models::storage::TaskStorage;
+use crate::models::task::{ArchivedTask, TaskStatus};
+use chrono::Utc;
+
+pub fn execute(selector: TaskSelector, remove_worktree_flag: bool, force: bool) -> TmResult<()> {
+    let tasks_file = get_tasks_file_path()?;
+    let storage = TaskStorage::load(&tasks_file)?;
+    let (project, title) = storage.resolve(&selector)?;
+    let task = storage.get_task(&project, &title)?;
+
+    // Refuse a dirty worktree before running pre-remove hooks, and run them without
+    // holding the task file lock, since they may run tm themselves
+    let remove = remove_worktree_flag && task.worktree_path.exists();
+    if remove {
+        git::check_clean(&task.worktree_path, force)?;
+        hooks::run_pre_remove(&project, task)?;
+    }
+
+    // Reload under the lock, the hooks may have changed the task
+    let (mut storage, _lock) = TaskStorage::load_locked(&tasks_file)?;
+    let mut task = storage.get_task(&project, &title)?.clone();
+
+    // Record where the work ended while the worktree still exists
+    let branch = git::current_branch(&task.worktree_path);
+    let commit = git::head_commit(&task.worktree_path);
+
+    // Remove the worktree before touching storage, so a failure aborts cleanly;
+    // the branch stays in the main repository for `tm unarchive`
+    if remove {
+        git::remove_worktree(&task.worktree_path, force)?;
+    }
+
+    storage.remove_task(&project, &title)?;
+    task.status = TaskStatus::Done;
+    task.touch();
+    storage.archive_task(
+        project.clone(),
+        ArchivedTask {
+            task: task.clone(),
+            archived_at: Utc::now(),
+            branch: branch.clone(),
+            commit: commit.clone(),
+        },
+    )?;
+    storage.save(&tasks_file)?;
+    history::record(&project, &title, EventKind::Archived);
+
+    println!("Archived task '{}' from project '{}'", title, project);
+    match (&branch, &commit) {
+        (Some(branch), Some(commit)) => println!("Branch: {} at {}", branch, short_commit(commit)),
+        (None, Some(commit)) => println!("Commit: {}", short_commit(commit)),
+        _ => {}
+    }
+    if remove {
+        println!("Removed worktree at: {}", task.worktree_path.display());
+    }
+
+    Ok(())
+}
+
+/// Move an archived task back to the active list, recreating its worktree from the
+/// kept branch, or from the archived commit if the branch has been deleted since
+pub fn unarchive(selector: TaskSelector) -> TmResult<()> {
+    let tasks_file = get_tasks_file_path()?;
+    let (mut storage, _lock) = TaskStorage::load_locked(&tasks_file)?;
+    let (project, title) = storage.resolve_archived(&selector)?;
+
+    if storage.get_task(&project, &title).is_ok() {
+        return Err(TmError::DuplicateTask { project, title });
+    }
+
+    let archived = storage.get_archived(&project, &title)?.clone();
+    let mut task = archived.task;
+
+    if !task.worktree_path.exists() {
+        let main_repo_path = match task.main_repo_path {
+            Some(ref path) => path.clone(),
+            None => Config::load()?.settings(&project, None)?.main_repo,
+        };
+        let branch = archived.branch.as_deref().unwrap_or(&task.title);
+
+        if let Some(parent) = task.worktree_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        match git::checkout_worktree(&main_repo_path, &task.worktree_path, branch) {
+            Err(TmError::BranchNotFound { .. }) if archived.commit.is_some() => {
+                git::create_worktree(
+                    &main_repo_path,
+                    &task.worktree_path,
+                    branch,
+                    archived.commit.as_deref(),
+                )?;
+                println!(
+                    "Recreated branch {} at {}",
+                    branch,
+                    short_commit(archived.commit.as_deref().unwrap_or_default())
+                );
+            }
+            result => {
+                result?;
+            }
+        }
+        println!("Created worktree at: {}", task.worktree_path.display());
+        println!("Branch: {}", branch);
+    }
+
+    storage.unarchive_task(&project, &title)?;
+    task.status = TaskStatus::InProgress;
+    task.touch();
+    storage.add_task(project.clone(), task)?;
+    storage.save(&tasks_file)?;
+    history::record(&project, &title, EventKind::Unarchived);
+
+    println!("Restored task '{}' to project '{}'", title, project);
+
+    Ok(())
+}
@@ -0,0 +1,294 @@
+use crate::cli::{Cli, Shell};
+use crate::config::{get_tasks_file_path, Config};
+use crate::error::TmResult;
+use crate::models::storage::TaskStorage;
+use crate::models::task::TaskStatus;
+use clap::{Arg, Command, CommandFactory, ValueEnum};
+use std::collections::BTreeSet;
+
+const BASH_COMPLETION: &str = r#"_tm() {
+    local IFS=$'\n'
+    COMPREPLY=($(command tm __complete -- "${COMP_WORDS[@]:1:COMP_CWORD}" 2>/dev/null))
+}
+
+complete -o default -F _tm tm
+"#;
+
+const ZSH_COMPLETION: &str = r#"_tm() {
+    local -a candidates
+    candidates=(${(f)"$(command tm __complete -- "${(@)words[2,CURRENT]}" 2>/dev/null)"})
+    if (( ${#candidates} )); then
+        compadd -- $candidates
+    else
+        _files
+    fi
+}
+
+(( $+functions[compdef] )) && compdef _tm tm
+"#;
+
+const FISH_COMPLETION: &str = r#"function __tm_complete
+    set -l words (commandline -opc)
+    set -e words[1]
+    command tm __complete -- $words (commandline -ct) 2>/dev/null
+end
+
+complete -c tm -f -a '(__tm_complete)'
+"#;
+
+/// Completion script for a shell, asking `tm __complete` for candidates
+pub fn script(shell: Shell) -> &'static str {
+    match shell {
+        Shell::Bash => BASH_COMPLETION,
+        Shell::Zsh => ZSH_COMPLETION,
+        Shell::Fish => FISH_COMPLETION,
+    }
+}
+
+/// Print the completion script for `tm completions <shell>`
+pub fn completions(shell: Shell) -> TmResult<()> {
+    print!("{}", script(shell));
+    Ok(())
+}
+
+/// Names the task-taking positionals can complete to
+struct Names {
+    projects: BTreeSet<String>,
+    /// (project, title, reference)
+    tasks: Vec<(String, String, Option<String>)>,
+    /// (project, title, reference) of archived tasks
+    archived: Vec<(String, String, Option<String>)>,
+}
+
+impl Names {
+    /// Read project and task names, treating unreadable files as empty
+    /// since completion must never print errors into the command line
+    fn load() -> Self {
+        let config = Config::load().unwrap_or_default();
+        let storage = get_tasks_file_path()
+            .and_then(|path| TaskStorage::load(&path))
+            .unwrap_or_default();
+
+        let mut projects: BTreeSet<String> = config.projects.keys().cloned().collect();
+        let mut tasks = Vec::new();
+        for (project, task) in storage.list_tasks(None) {
+            projects.insert(project.to_string());
+            tasks.push((
+                project.to_string(),
+                task.title.clone(),
+                task.reference.clone(),
+            ));
+        }
+        tasks.sort();
+
+        let mut archived: Vec<_> = storage
+            .list_archived(None)
+            .into_iter()
+            .map(|(project, archived)| {
+                (
+                    project.to_string(),
+                    archived.task.title.clone(),
+                    archived.task.reference.clone(),
+                )
+            })
+            .collect();
+        archived.sort();
+
+        Self {
+            projects,
+            tasks,
+            archived,
+        }
+    }
+
+    fn is_project(&self, name: &str) -> bool {
+        self.projects.contains(name)
+    }
+
+    /// Everything a task selector accepts: projects, `project/title` and references
+    fn selectors(&self) -> Vec<String> {
+        let mut result: Vec<String> = self.projects.iter().cloned().collect();
+        result.extend(Self::task_selectors(&self.tasks));
+        result
+    }
+
+    /// Selectors of archived tasks, for `tm unarchive`
+    fn archived_selectors(&self) -> Vec<String> {
+        Self::task_selectors(&self.archived)
+    }
+
+    fn task_selectors(tasks: &[(String, String, Option<String>)]) -> Vec<String> {
+        let mut result = Vec::new();
+        for (project, title, reference) in tasks {
+            result.push(format!("{}/{}", project, title));
+            result.extend(reference.clone());
+        }
+        result
+    }
+
+    fn titles(&self, project: &str) -> Vec<String> {
+        Self::titles_in(&self.tasks, project)
+    }
+
+    fn archived_titles(&self, project: &str) -> Vec<String> {
+        Self::titles_in(&self.archived, project)
+    }
+
+    fn titles_in(tasks: &[(String, String, Option<String>)], project: &str) -> Vec<String> {
+        tasks
+            .iter()
+            .filter(|(p, _, _)| p == project)
+            .map(|(_, title, _)| title.clone())
+            .collect()
+    }
+}
+
+fn possible_values(arg: &Arg) -> Vec<String> {
+    arg.get_possible_values()
+        .iter()
+        .filter(|v| !v.is_hide_set())
+        .map(|v| v.get_name().to_string())
+        .collect()
+}
+
+fn statuses() -> Vec<String> {
+    TaskStatus::value_variants()
+        .iter()
+        .map(|s| s.as_str().to_string())
+        .collect()
+}
+
+/// Candidates for the value of an option such as `--project` or `--level`
+fn option_values(arg: &Arg) -> Vec<String> {
+    match arg.get_id().as_str() {
+        "project" => Names::load().projects.into_iter().collect(),
+        "level" => Config::load()
+            .and_then(|config| config.levels())
+            .map(|levels| levels.names().iter().map(|n| n.to_string()).collect())
+            .unwrap_or_default(),
+        _ => possible_values(arg),
+    }
+}
+
+/// Candidates for the `index`-th positional value of `command`,
+/// given the positional values typed before it
+fn positional_values(command: &Command, index: usize, typed: &[String]) -> Vec<String> {
+    // A multi-valued positional takes every remaining value
+    let mut offset = index;
+    let arg = command.get_positionals().find(|arg| {
+        let multiple = arg.get_num_args().is_some_and(|n| n.max_values() > 1);
+        if offset == 0 || multiple {
+            true
+        } else {
+            offset -= 1;
+            false
+        }
+    });
+    let Some(arg) = arg else {
+        return Vec::new();
+    };
+
+    match arg.get_id().as_str() {
+        "project" | "name" | "from_project" | "to_project" => {
+            Names::load().projects.into_iter().collect()
+        }
+        "task" if command.get_name() == "unarchive" => Names::load().archived_selectors(),
+        "task" => Names::load().selectors(),
+        "title" if command.get_name() == "unarchive" => {
+            Names::load().archived_titles(&typed[index - 1])
+        }
+        "title" => Names::load().titles(&typed[index - 1]),
+        // `tm status [TASK | PROJECT TITLE] STATE`
+        "args" => match offset {
+            0 => {
+                let mut values = Names::load().selectors();
+                values.extend(statuses());
+                values
+            }
+            1 => {
+                let names = Names::load();
+                let mut values = if names.is_project(&typed[0]) {
+                    names.titles(&typed[0])
+                } else {
+                    Vec::new()
+                };
+                values.extend(statuses());
+                values
+            }
+            _ => statuses(),
+        },
+        _ => possible_values(arg),
+    }
+}
+
+/// Print completion candidates for a partially typed command line
+/// `words` are the words after `tm`, the last one being the word under the cursor
+pub fn execute(mut words: Vec<String>) -> TmResult<()> {
+    let current = words.pop().unwrap_or_default();
+
+    let root = Cli::command();
+    let mut command = &root;
+    let mut typed: Vec<String> = Vec::new();
+    let mut pending: Option<&Arg> = None;
+
+    // Walk the typed words to find the subcommand, its positional values and
+    // whether the word under the cursor is the value of an option
+    for word in &words {
+        if pending.take().is_some() {
+            continue;
+        }
+
+        if let Some(long) = word.strip_prefix("--") {
+            if !long.contains('=') {
+                pending = command
+                    .get_arguments()
+                    .find(|a| a.get_long() == Some(long))
+                    .filter(|a| a.get_action().takes_values());
+            }
+        } else if let Some(short) = word.strip_prefix('-').filter(|s| s.len() == 1) {
+            let short = short.chars().next();
+            pending = command
+                .get_arguments()
+                .find(|a| a.get_short() == short)
+                .filter(|a| a.get_action().takes_values());
+        } else if word.starts_with('-') {
+            // Combined short flags or an attached value, e.g. "-wf" or "-pmyproject"
+        } else if let Some(sub) = typed
+            .is_empty()
+            .then(|| command.find_subcommand(word))
+            .flatten()
+        {
+            command = sub;
+        } else {
+            typed.push(word.clone());
+        }
+    }
+
+    let candidates = if let Some(arg) = pending {
+        option_values(arg)
+    } else if current.starts_with('-') {
+        command
+            .get_arguments()
+            .filter(|a| !a.is_hide_set())
+            .filter_map(|a| a.get_long().map(|long| format!("--{}", long)))
+            .collect()
+    } else if command.has_subcommands() {
+        command
+            .get_subcommands()
+            .filter(|c| !c.is_hide_set())
+            .map(|c| c.get_name().to_string())
+            .collect()
+    } else {
+        positional_values(command, typed.len(), &typed)
+    };
+
+    let candidates: BTreeSet<String> = candidates
+        .into_iter()
+        .filter(|c| c.starts_with(&current))
+        .collect();
+    for candidate in candidates {
+        println!("{}", candidate);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use crate::config::get_tasks_file_path;
+use crate::error::{TmError, TmResult};
+use crate::models::storage::TaskStorage;
+
+pub fn execute(json: bool, short: bool, quiet: bool) -> TmResult<()> {
+    let tasks_file = get_tasks_file_path()?;
+    let storage = TaskStorage::load(&tasks_file)?;
+
+    let cwd = std::env::current_dir()?;
+    let Some((project, task)) = storage.task_at(&cwd) else {
+        // Prompts call this in every directory, so stay silent when asked to
+        if quiet {
+            std::process::exit(1);
+        }
+        return Err(TmError::NotInTask { path: cwd });
+    };
+
+    if json {
+        let value = serde_json::json!({
+            "project": project,
+            "title": task.title,
+            "reference": task.reference,
+            "status": task.status,
+            "worktree_path": task.worktree_path,
+        });
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(());
+    }
+
+    if short {
+        println!("{}", task.reference.as_deref().unwrap_or(&task.title));
+        return Ok(());
+    }
+
+    // Format: "project/title" followed by the reference, if any
+    match task.reference {
+        Some(ref reference) => println!("{}/{} {}", project, task.title, reference),
+        None => println!("{}/{}", project, task.title),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,246 @@
+use crate::commands::import::task_for_branch;
+use crate::config::{get_tasks_file_path, Config};
+use crate::error::TmResult;
+use crate::git;
+use crate::models::history::{self, EventKind};
+use crate::models::storage::TaskStorage;
+use colored::Colorize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A stored task whose worktree is gone or broken
+struct OrphanedTask {
+    project: String,
+    title: String,
+    reason: String,
+}
+
+/// A worktree registered in git that no task refers to
+struct UntrackedWorktree {
+    project: String,
+    main_repo: PathBuf,
+    path: PathBuf,
+    branch: Option<String>,
+}
+
+/// A `.git/worktrees/<name>` entry git considers stale
+struct PrunableWorktree {
+    main_repo: PathBuf,
+    name: String,
+}
+
+/// A branch following the naming scheme that isn't checked out anywhere
+struct DanglingBranch {
+    main_repo: PathBuf,
+    branch: String,
+}
+
+pub fn execute(fix: bool) -> TmResult<()> {
+    let levels = Config::load()?.levels()?;
+    let tasks_file = get_tasks_file_path()?;
+    let (mut storage, _lock) = TaskStorage::load_locked(&tasks_file)?;
+
+    let mut orphaned = Vec::new();
+    let mut untracked = Vec::new();
+    let mut prunable = Vec::new();
+    let mut dangling = Vec::new();
+
+    // Step 1: Check every stored task against the filesystem
+    // and collect the main repositories they belong to
+    let mut main_repos: BTreeMap<PathBuf, String> = BTreeMap::new();
+    for (project, task) in storage.list_tasks(None) {
+        if let Err(e) = git::validate_worktree(&task.worktree_path) {
+            orphaned.push(OrphanedTask {
+                project: project.to_string(),
+                title: task.title.clone(),
+                reason: e.to_string(),
+            });
+        }
+
+        if let Some(main_repo) = task.main_repo() {
+            let key = main_repo.canonicalize().unwrap_or(main_repo);
+            main_repos.entry(key).or_insert_with(|| project.to_string());
+        }
+    }
+
+    // Step 2: Cross-check each main repository's worktrees and branches;
+    // worktrees kept by `tm archive` and branches kept by `tm archive -w`
+    // are waiting for `tm unarchive`
+    let archived = storage.list_archived(None);
+    let archived_branches: Vec<&str> = archived
+        .iter()
+        .filter_map(|(_, archived)| archived.branch.as_deref())
+        .collect();
+    let tracked_paths: Vec<&Path> = storage
+        .list_tasks(None)
+        .into_iter()
+        .map(|(_, task)| task.worktree_path.as_path())
+        .chain(
+            archived
+                .iter()
+                .map(|(_, archived)| archived.task.worktree_path.as_path()),
+        )
+        .collect();
+    for (main_repo, project) in &main_repos {
+        let worktrees = match git::list_worktrees(main_repo) {
+            Ok(worktrees) => worktrees,
+            Err(e) => {
+                eprintln!(
+                    "{} skipping {}: {}",
+                    "warning:".yellow(),
+                    main_repo.display(),
+                    e
+                );
+                continue;
+            }
+        };
+
+        let mut checked_out: Vec<String> = git::current_branch(main_repo).into_iter().collect();
+
+        for wt in worktrees {
+            if let Some(ref branch) = wt.branch {
+                checked_out.push(branch.clone());
+            }
+
+            if wt.prunable {
+                prunable.push(PrunableWorktree {
+                    main_repo: main_repo.clone(),
+                    name: wt.name,
+                });
+                continue;
+            }
+
+            let tracked = tracked_paths
+                .iter()
+                .any(|path| git::same_path(path, &wt.path));
+            if !tracked {
+                untracked.push(UntrackedWorktree {
+                    project: project.clone(),
+                    main_repo: main_repo.clone(),
+                    path: wt.path,
+                    branch: wt.branch,
+                });
+            }
+        }
+
+        for branch in git::list_local_branches(main_repo)? {
+            if checked_out.contains(&branch)
+                || archived_branches.contains(&branch.as_str())
+                || git::parse_branch_name(&branch).is_none()
+            {
+                continue;
+            }
+            dangling.push(DanglingBranch {
+                main_repo: main_repo.clone(),
+                branch,
+            });
+        }
+    }
+
+    // Step 3: Report
+    let problems = orphaned.len() + untracked.len() + prunable.len() + dangling.len();
+
+    if !orphaned.is_empty() {
+        println!("{}", "Orphaned tasks (worktree missing or invalid):".bold());
+        for o in &orphaned {
+            println!("  {}/{}: {}", o.project, o.title, o.reason);
+        }
+    }
+
+    if !untracked.is_empty() {
+        println!("{}", "Untracked worktrees (not registered in tm):".bold());
+        for u in &untracked {
+            println!(
+                "  {} ({})",
+                u.path.display(),
+                u.branch.as_deref().unwrap_or("detached HEAD")
+            );
+        }
+    }
+
+    if !prunable.is_empty() {
+        println!("{}", "Prunable worktree entries:".bold());
+        for p in &prunable {
+            println!("  {}/.git/worktrees/{}", p.main_repo.display(), p.name);
+        }
+    }
+
+    if !dangling.is_empty() {
+        println!("{}", "Branches without worktrees:".bold());
+        for d in &dangling {
+            println!("  {} ({})", d.branch, d.main_repo.display());
+        }
+    }
+
+    if problems == 0 {
+        println!("{}", "No problems found.".green());
+        return Ok(());
+    }
+
+    if !fix {
+        println!();
+        println!(
+            "{} problem(s) found. Run 'tm doctor --fix' to repair.",
+            problems
+        );
+        return Ok(());
+    }
+
+    // Step 4: Repair
+    println!();
+
+    for p in &prunable {
+        match git::prune_worktree(&p.main_repo, &p.name) {
+            Ok(()) => println!("Pruned .git/worktrees/{}", p.name),
+            Err(e) => println!("Could not prune .git/worktrees/{}: {}", p.name, e),
+        }
+    }
+
+    for o in &orphaned {
+        storage.remove_task(&o.project, &o.title)?;
+        println!("Removed orphaned task '{}/{}'", o.project, o.title);
+    }
+
+    let mut imported = Vec::new();
+    for u in &untracked {
+        let task = u
+            .branch
+            .as_deref()
+            .and_then(|branch| task_for_branch(branch, &u.path, &u.main_repo, &levels));
+
+        match task {
+            Some(task) => {
+                let title = task.title.clone();
+                match storage.add_task(u.project.clone(), task) {
+                    Ok(()) => {
+                        println!("Imported '{}' into project '{}'", title, u.project);
+                        imported.push((u.project.clone(), title));
+                    }
+                    Err(e) => println!("Could not import {}: {}", u.path.display(), e),
+                }
+            }
+            None => println!(
+                "Left {} untracked: branch does not match the <level>/<id>-<name> naming scheme",
+                u.path.display()
+            ),
+        }
+    }
+
+    if !dangling.is_empty() {
+        println!(
+            "Left {} branch(es) without worktrees untouched; delete them with 'git branch -d' if no longer needed",
+            dangling.len()
+        );
+    }
+
+    storage.save(&tasks_file)?;
+
+    for o in &orphaned {
+        history::record(&o.project, &o.title, EventKind::Removed);
+    }
+    for (project, title) in &imported {
+        history::record(project, title, EventKind::Created);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,217 @@
+use crate::config::get_tasks_file_path;
+use crate::error::{TmError, TmResult};
+use crate::models::history::{self, EventKind};
+use crate::models::selector::TaskSelector;
+use crate::models::storage::TaskStorage;
+use crate::models::task::Task;
+use crate::prompt;
+use serde::{Deserialize, Serialize};
+use std::fs::{DirBuilder, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::DirBuilderExt;
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The metadata `tm edit` can change; an empty value clears the field
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Fields {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub reference: Option<String>,
+    #[serde(default)]
+    pub remote_url: Option<String>,
+    #[serde(default)]
+    pub api_url: Option<String>,
+}
+
+impl Fields {
+    fn is_empty(&self) -> bool {
+        *self == Fields::default()
+    }
+
+    /// The task's current values, with every field present so the editor shows them all
+    fn of(task: &Task) -> Self {
+        let value = |field: &Option<String>| Some(field.clone().unwrap_or_default());
+        Self {
+            description: value(&task.description),
+            reference: value(&task.reference),
+            remote_url: value(&task.remote_url),
+            api_url: value(&task.api_url),
+        }
+    }
+
+    /// Set the given fields on a task, returning the names of those that changed
+    fn apply(self, task: &mut Task) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        for (name, value, field) in [
+            ("description", self.description, &mut task.description),
+            ("reference", self.reference, &mut task.reference),
+            ("remote_url", self.remote_url, &mut task.remote_url),
+            ("api_url", self.api_url, &mut task.api_url),
+        ] {
+            let Some(value) = value else {
+                continue;
+            };
+            let value = Some(value.trim().to_string()).filter(|v| !v.is_empty());
+            if *field != value {
+                *field = value;
+                changed.push(name);
+            }
+        }
+        changed
+    }
+}
+
+const EDITOR_HEADER: &str = "\
+# Lines starting with '#' are ignored. Leave a value empty to clear it.
+# Only these fields can be changed here; use 'tm rename' for the title and 'tm status' for the status.
+";
+
+/// Open the task's fields in $VISUAL or $EDITOR until they parse, None if the user gives up
+fn edit_in_editor(project: &str, task: &Task) -> TmResult<Option<Fields>> {
+    let content = format!(
+        "# Editing {}/{}\n{}\n{}",
+        project,
+        task.title,
+        EDITOR_HEADER,
+        toml::to_string(&Fields::of(task))?
+    );
+
+    // A fresh directory only the user can enter, so nobody can plant a symlink
+    // where the file is written; removed again whatever happens
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    let dir = std::env::temp_dir().join(format!("tm-edit-{}-{}", std::process::id(), nanos));
+    DirBuilder::new().mode(0o700).create(&dir)?;
+
+    let result = edit_file(&dir.join("task.toml"), &content);
+    let _ = std::fs::remove_dir_all(&dir);
+    result
+}
+
+fn edit_file(path: &Path, content: &str) -> TmResult<Option<Fields>> {
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?
+        .write_all(content.as_bytes())?;
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+
+    loop {
+        // Through the shell, so editors with arguments like "code --wait" work
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg(format!("{} \"$1\"", editor))
+            .arg("sh")
+            .arg(path)
+            .status()?;
+        if !status.success() {
+            return Err(TmError::InvalidInput {
+                field: "editor".to_string(),
+                reason: format!("'{}' exited with {}", editor, status),
+            });
+        }
+
+        match toml::from_str::<Fields>(&std::fs::read_to_string(path)?) {
+            Ok(fields) => return Ok(Some(fields)),
+            Err(e) => {
+                eprintln!("Invalid task: {}", e);
+                if !prompt::confirm("Edit again?", true)? {
+                    return Ok(None);
+                }
+            }
+        }
+    }
+}
+
+pub fn execute(selector: TaskSelector, fields: Fields) -> TmResult<()> {
+    if !fields.is_empty() {
+        return save(&selector, fields);
+    }
+
+    // Without field flags, edit in the editor; the task file isn't locked meanwhile
+    let storage = TaskStorage::load(&get_tasks_file_path()?)?;
+    let (project, title) = storage.resolve(&selector)?;
+    let task = storage.get_task(&project, &title)?;
+    let fields = edit_in_editor(&project, task)?.ok_or(TmError::Cancelled)?;
+
+    let selector = TaskSelector::InProject {
+        project,
+        query: title,
+    };
+    save(&selector, fields)
+}
+
+/// Apply the fields to the stored task and save it
+fn save(selector: &TaskSelector, fields: Fields) -> TmResult<()> {
+    let tasks_file = get_tasks_file_path()?;
+    let (mut storage, _lock) = TaskStorage::load_locked(&tasks_file)?;
+    let (project, title) = storage.resolve(selector)?;
+
+    let task = storage.get_task_mut(&project, &title)?;
+    let changed = fields.apply(task);
+    if changed.is_empty() {
+        println!("No changes to task '{}'", title);
+        return Ok(());
+    }
+
+    task.touch();
+    storage.save(&tasks_file)?;
+    history::record(
+        &project,
+        &title,
+        EventKind::Edited {
+            fields: changed.iter().map(|f| f.to_string()).collect(),
+        },
+    );
+
+    println!("Updated {} of task '{}'", changed.join(", "), title);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_apply_fields() {
+        let mut task = Task::new("feature/JIRA-1-auth".to_string(), PathBuf::from("/tmp"))
+            .with_description("Auth".to_string())
+            .with_reference("JIRA-1".to_string());
+
+        let changed = Fields {
+            description: Some("Auth".to_string()),
+            reference: Some(" ".to_string()),
+            remote_url: Some("https://example.com/repo".to_string()),
+            api_url: None,
+        }
+        .apply(&mut task);
+
+        assert_eq!(changed, ["reference", "remote_url"]);
+        assert_eq!(task.description.as_deref(), Some("Auth"));
+        assert_eq!(task.reference, None);
+        assert_eq!(task.remote_url.as_deref(), Some("https://example.com/repo"));
+    }
+
+    #[test]
+    fn test_editor_fields_reject_unknown_keys() {
+        assert!(toml::from_str::<Fields>("titel = \"x\"").is_err());
+        assert_eq!(
+            toml::from_str::<Fields>("# comment\nreference = \"\"").unwrap(),
+            Fields {
+                reference: Some(String::new()),
+                ..Fields::default()
+            }
+        );
+    }
+}
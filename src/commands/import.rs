@@ -0,0 +1,115 @@
+use crate::config::{get_tasks_file_path, Config};
+use crate::error::TmResult;
+use crate::git;
+use crate::level::Levels;
+use crate::models::history::{self, EventKind};
+use crate::models::{storage::TaskStorage, task::Task};
+use std::path::{Path, PathBuf};
+
+/// Build a task for a worktree whose branch follows the `<prefix>/<id>-<name>` scheme,
+/// titled with the level name like `tm add` does
+pub fn task_for_branch(
+    branch: &str,
+    worktree_path: &Path,
+    main_repo_path: &Path,
+    levels: &Levels,
+) -> Option<Task> {
+    let components = git::parse_branch_name(branch)?;
+    let level = levels.by_prefix(&components.level)?;
+    let (_, rest) = branch.split_once('/')?;
+
+    let mut task = Task::new(
+        format!("{}/{}", level.as_str(), rest),
+        worktree_path.to_path_buf(),
+    )
+    .with_main_repo_path(main_repo_path.to_path_buf())
+    .with_reference(components.id);
+    task.start_commit = git::fork_point(main_repo_path, branch, None);
+    Some(task)
+}
+
+pub fn execute(project: String, main_repo_path: PathBuf, dry_run: bool) -> TmResult<()> {
+    // Step 1: Validate main repo path is a git repository
+    git::validate_worktree(&main_repo_path)?;
+
+    // Step 2: Enumerate the worktrees registered in the main repository
+    let levels = Config::load()?.levels()?;
+    let worktrees = git::list_worktrees(&main_repo_path)?;
+
+    let tasks_file = get_tasks_file_path()?;
+    let (mut storage, _lock) = TaskStorage::load_locked(&tasks_file)?;
+
+    let mut imported = Vec::new();
+    let mut skipped = Vec::new();
+    let mut unmatched = Vec::new();
+
+    for wt in worktrees {
+        if !wt.path.exists() {
+            skipped.push((wt.path, "worktree directory is missing".to_string()));
+            continue;
+        }
+
+        let already_tracked = storage
+            .list_tasks(None)
+            .iter()
+            .any(|(_, task)| git::same_path(&task.worktree_path, &wt.path));
+        if already_tracked {
+            skipped.push((wt.path, "already tracked".to_string()));
+            continue;
+        }
+
+        // Step 3: Parse the branch back into level/id/name
+        let Some(branch) = wt.branch else {
+            unmatched.push((wt.path, "detached HEAD".to_string()));
+            continue;
+        };
+
+        // Step 4: Register the task
+        let Some(task) = task_for_branch(&branch, &wt.path, &main_repo_path, &levels) else {
+            unmatched.push((wt.path, branch));
+            continue;
+        };
+
+        let title = task.title.clone();
+        if let Err(e) = storage.add_task(project.clone(), task) {
+            skipped.push((wt.path, e.to_string()));
+            continue;
+        }
+
+        imported.push((title, wt.path));
+    }
+
+    if !dry_run && !imported.is_empty() {
+        storage.save(&tasks_file)?;
+        for (title, _) in &imported {
+            history::record(&project, title, EventKind::Created);
+        }
+    }
+
+    let verb = if dry_run { "Would import" } else { "Imported" };
+    for (title, path) in &imported {
+        println!("{} '{}' ({})", verb, title, path.display());
+    }
+
+    for (path, reason) in &skipped {
+        println!("Skipped {}: {}", path.display(), reason);
+    }
+
+    if !unmatched.is_empty() {
+        println!();
+        println!("Worktrees not matching the <level>/<id>-<name> naming scheme:");
+        for (path, branch) in &unmatched {
+            println!("  {} ({})", path.display(), branch);
+        }
+    }
+
+    println!();
+    println!(
+        "{} {} task(s) into project '{}'",
+        verb,
+        imported.len(),
+        project
+    );
+
+    Ok(())
+}
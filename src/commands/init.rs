@@ -0,0 +1,119 @@
+use crate::cli::Shell;
+use crate::commands::complete;
+use crate::error::TmResult;
+
+// Wrap `tm switch`, `tm pick` and `tm ui` so they change directory instead of printing the path;
+// output that isn't a directory (errors, --help) is passed through
+const BASH_WRAPPER: &str = r#"tm() {
+    if [ "$1" = "switch" ] || [ "$1" = "pick" ] || [ "$1" = "ui" ]; then
+        local dir
+        dir="$(command tm "$@")" || return
+        if [ -d "$dir" ]; then
+            cd -- "$dir"
+        elif [ -n "$dir" ]; then
+            printf '%s\n' "$dir"
+        fi
+    else
+        command tm "$@"
+    fi
+}
+"#;
+
+const BASH_PROMPT: &str = r#"
+__tm_prompt() {
+    local task
+    task="$(command tm current --short --quiet 2>/dev/null)" || return 0
+    printf '(%s) ' "$task"
+}
+
+case "$PS1" in
+    *__tm_prompt*) ;;
+    *) PS1='$(__tm_prompt)'"$PS1" ;;
+esac
+"#;
+
+const ZSH_WRAPPER: &str = r#"tm() {
+    if [[ "$1" == "switch" || "$1" == "pick" || "$1" == "ui" ]]; then
+        local dir
+        dir="$(command tm "$@")" || return
+        if [[ -d "$dir" ]]; then
+            cd -- "$dir"
+        elif [[ -n "$dir" ]]; then
+            print -r -- "$dir"
+        fi
+    else
+        command tm "$@"
+    fi
+}
+"#;
+
+const ZSH_PROMPT: &str = r#"
+__tm_prompt() {
+    local task
+    task="$(command tm current --short --quiet 2>/dev/null)" || return 0
+    print -n -- "($task) "
+}
+
+setopt prompt_subst
+[[ "$PROMPT" == *__tm_prompt* ]] || PROMPT='$(__tm_prompt)'"$PROMPT"
+"#;
+
+const FISH_WRAPPER: &str = r#"function tm --wraps tm --description 'tm, changing directory on switch'
+    if contains -- "$argv[1]" switch pick ui
+        set -l dir (command tm $argv); or return
+        if test -d "$dir"
+            cd -- $dir
+        else if test -n "$dir"
+            printf '%s\n' $dir
+        end
+    else
+        command tm $argv
+    end
+end
+"#;
+
+const FISH_PROMPT: &str = r#"
+function __tm_prompt
+    set -l task (command tm current --short --quiet 2>/dev/null); or return 0
+    printf '(%s) ' $task
+end
+
+if functions -q fish_prompt; and not functions -q __tm_original_fish_prompt
+    functions -c fish_prompt __tm_original_fish_prompt
+    function fish_prompt
+        __tm_prompt
+        __tm_original_fish_prompt
+    end
+end
+"#;
+
+pub fn execute(shell: Shell, prompt: bool) -> TmResult<()> {
+    let (wrapper, prompt_segment) = match shell {
+        Shell::Bash => (BASH_WRAPPER, BASH_PROMPT),
+        Shell::Zsh => (ZSH_WRAPPER, ZSH_PROMPT),
+        Shell::Fish => (FISH_WRAPPER, FISH_PROMPT),
+    };
+
+    let setup = match shell {
+        Shell::Fish => "tm init fish | source",
+        Shell::Bash | Shell::Zsh => "eval \"$(tm init <shell>)\"",
+    };
+
+    println!(
+        "# tm shell integration, generated by 'tm init {}'",
+        shell.as_str()
+    );
+    println!(
+        "# Load it from your shell's startup file: {}",
+        setup.replace("<shell>", shell.as_str())
+    );
+    println!();
+    print!("{}", wrapper);
+    if prompt {
+        print!("{}", prompt_segment);
+    }
+    println!();
+    print!("{}", complete::script(shell));
+
+    Ok(())
+}
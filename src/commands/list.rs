@@ -1,100 +1,539 @@
-use crate::cli::OutputFormat;
-use crate::config::get_tasks_file_path;
-use crate::error::TmResult;
+use crate::cli::{Column, OutputFormat, SortKey};
+use crate::config::{get_tasks_file_path, Config};
+use crate::error::{TmError, TmResult};
+use crate::format::short_commit;
+use crate::git::{self, WorktreeInfo};
+use crate::level::{Level, Levels};
 use crate::models::storage::TaskStorage;
-use colored::Colorize;
+use crate::models::task::{ArchivedTask, Task, TaskStatus};
+use chrono::{DateTime, Local, Utc};
+use colored::{Color, ColoredString, Colorize};
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
 
-pub fn execute(project: Option<String>, format: OutputFormat) -> TmResult<()> {
+/// Live state of a task's worktree, shown with --status and in `tm ui`
+pub enum Health {
+    Ok(WorktreeInfo),
+    Missing,
+    Error(String),
+}
+
+impl Health {
+    pub fn of(task: &Task) -> Self {
+        match git::get_worktree_info(&task.worktree_path, task.base_branch.as_deref()) {
+            Ok(info) => Health::Ok(info),
+            Err(TmError::WorktreePathNotFound { .. }) => Health::Missing,
+            Err(e) => Health::Error(e.to_string()),
+        }
+    }
+
+    pub fn branch(&self) -> &str {
+        match self {
+            Health::Ok(info) => info.branch.as_deref().unwrap_or("(detached)"),
+            _ => "-",
+        }
+    }
+
+    /// Short summary like "dirty ↑2 ↓1", "clean" or "missing"
+    pub fn summary(&self) -> String {
+        match self {
+            Health::Ok(info) => {
+                let mut summary = if info.has_uncommitted_changes {
+                    "dirty".to_string()
+                } else {
+                    "clean".to_string()
+                };
+                if info.ahead > 0 {
+                    summary.push_str(&format!(" ↑{}", info.ahead));
+                }
+                if info.behind > 0 {
+                    summary.push_str(&format!(" ↓{}", info.behind));
+                }
+                summary
+            }
+            Health::Missing => "missing".to_string(),
+            Health::Error(_) => "error".to_string(),
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        use serde_json::json;
+
+        match self {
+            Health::Ok(info) => json!({
+                "missing": false,
+                "branch": info.branch,
+                "dirty": info.has_uncommitted_changes,
+                "base": info.base,
+                "ahead": info.ahead,
+                "behind": info.behind,
+            }),
+            Health::Missing => json!({ "missing": true }),
+            Health::Error(e) => json!({ "missing": false, "error": e }),
+        }
+    }
+}
+
+/// Which tasks `tm list` shows
+pub struct Filters {
+    /// Project name or pattern
+    pub project: Option<String>,
+    pub state: Option<TaskStatus>,
+    pub level: Option<Level>,
+    /// Reference pattern
+    pub reference: Option<String>,
+    /// Directory the worktree has to be in
+    pub path: Option<PathBuf>,
+    /// Only tasks with uncommitted changes
+    pub dirty: bool,
+}
+
+impl Filters {
+    /// Check everything but `dirty`, which needs the worktree's state
+    fn matches(&self, project: &str, task: &Task, path: Option<&Path>) -> bool {
+        self.project
+            .as_deref()
+            .is_none_or(|pattern| matches_pattern(pattern, project))
+            && self.state.is_none_or(|state| task.status == state)
+            && self.level.as_ref().is_none_or(|level| {
+                task.title
+                    .split_once('/')
+                    .is_some_and(|(prefix, _)| prefix == level.as_str())
+            })
+            && self.reference.as_deref().is_none_or(|pattern| {
+                task.reference
+                    .as_deref()
+                    .is_some_and(|reference| matches_pattern(pattern, reference))
+            })
+            && path.is_none_or(|path| task.worktree_path.starts_with(path))
+    }
+}
+
+/// Match text against a pattern where `*` stands for any characters and `?` for one,
+/// ignoring case. A pattern without wildcards has to match exactly
+fn matches_pattern(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    // Backtrack to the last `*` on a mismatch, letting it swallow one more character
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Order two tasks for `--sort`; times sort most recent first and missing values last
+fn compare(a: (&str, &Task), b: (&str, &Task), sort: SortKey) -> Ordering {
+    let time = |task: &Task| match sort {
+        SortKey::Updated => task.updated_at,
+        SortKey::Switched => task.last_switched_at,
+        _ => task.created_at,
+    };
+
+    match sort {
+        SortKey::Project => (a.0, &a.1.title).cmp(&(b.0, &b.1.title)),
+        SortKey::Title => (&a.1.title, a.0).cmp(&(&b.1.title, b.0)),
+        SortKey::Status => a.1.status.cmp(&b.1.status),
+        SortKey::Reference => match (&a.1.reference, &b.1.reference) {
+            (Some(a), Some(b)) => a.cmp(b),
+            (a, b) => b.is_some().cmp(&a.is_some()),
+        },
+        SortKey::Path => a.1.worktree_path.cmp(&b.1.worktree_path),
+        SortKey::Created | SortKey::Updated | SortKey::Switched => time(b.1).cmp(&time(a.1)),
+    }
+}
+
+pub fn execute(
+    filters: Filters,
+    status: bool,
+    format: OutputFormat,
+    archived: bool,
+    sort: Option<SortKey>,
+    columns: Vec<Column>,
+) -> TmResult<()> {
     let tasks_file = get_tasks_file_path()?;
     let storage = TaskStorage::load(&tasks_file)?;
+    let path = filters
+        .path
+        .as_deref()
+        .map(std::path::absolute)
+        .transpose()?;
 
-    let tasks = storage.list_tasks(project.as_deref());
+    if archived {
+        return list_archived(&storage, &filters, path.as_deref(), format, sort);
+    }
 
-    if tasks.is_empty() {
+    // Projects come in name order, and tasks in the order they were added
+    let mut tasks: Vec<(&str, &Task)> = storage
+        .list_tasks(None)
+        .into_iter()
+        .filter(|(project, task)| filters.matches(project, task, path.as_deref()))
+        .collect();
+    if let Some(sort) = sort {
+        tasks.sort_by(|a, b| compare(*a, *b, sort));
+    }
+
+    let columns = if columns.is_empty() {
+        let mut columns = vec![
+            Column::Project,
+            Column::Title,
+            Column::Status,
+            Column::Reference,
+        ];
+        if status {
+            columns.extend([Column::Branch, Column::Worktree]);
+        }
+        columns.push(Column::Path);
+        columns
+    } else {
+        columns
+    };
+
+    // Inspecting every worktree is comparatively slow, so only do it when needed
+    let needs_health = status
+        || filters.dirty
+        || (matches!(format, OutputFormat::Table)
+            && columns
+                .iter()
+                .any(|c| matches!(c, Column::Branch | Column::Worktree)));
+    let mut rows: Vec<(&str, &Task, Option<Health>)> = tasks
+        .into_iter()
+        .map(|(project, task)| (project, task, needs_health.then(|| Health::of(task))))
+        .collect();
+    if filters.dirty {
+        rows.retain(|(_, _, health)| {
+            matches!(health, Some(Health::Ok(info)) if info.has_uncommitted_changes)
+        });
+    }
+
+    if rows.is_empty() {
         println!("No tasks found.");
         return Ok(());
     }
 
     match format {
-        OutputFormat::Table => print_table(&tasks),
-        OutputFormat::Simple => print_simple(&tasks),
-        OutputFormat::Json => print_json(&tasks)?,
+        OutputFormat::Table => print_table(&rows, &columns, &Config::load()?.levels()?),
+        OutputFormat::Simple => print_simple(&rows),
+        OutputFormat::Json => print_json(&rows, status)?,
     }
 
     Ok(())
 }
 
-fn print_table(tasks: &[(&str, &crate::models::task::Task)]) {
-    // Calculate column widths based on content
+/// Text of a table cell, with the color it is shown in
+fn cell(
+    column: Column,
+    project: &str,
+    task: &Task,
+    health: Option<&Health>,
+    levels: &Levels,
+) -> (String, Option<Color>) {
+    let time = |time: Option<DateTime<Utc>>| match time {
+        Some(time) => time
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+        None => "-".to_string(),
+    };
+
+    match column {
+        Column::Project => (project.to_string(), None),
+        // Titles start with the level name, which may have a configured color
+        Column::Title => {
+            let color = task
+                .title
+                .split_once('/')
+                .and_then(|(level, _)| levels.find(level))
+                .and_then(|level| level.color());
+            (task.title.clone(), color)
+        }
+        Column::Status => (task.status.as_str().to_string(), None),
+        Column::Reference => (
+            task.reference.clone().unwrap_or_else(|| "-".to_string()),
+            None,
+        ),
+        Column::Description => (
+            task.description.clone().unwrap_or_else(|| "-".to_string()),
+            None,
+        ),
+        Column::Branch => (health.map_or("-", |h| h.branch()).to_string(), None),
+        Column::Worktree => match health {
+            Some(h) => {
+                let color = match h {
+                    Health::Ok(info) if !info.has_uncommitted_changes => Color::Green,
+                    Health::Ok(_) => Color::Yellow,
+                    _ => Color::Red,
+                };
+                (h.summary(), Some(color))
+            }
+            None => ("-".to_string(), None),
+        },
+        Column::Path => (task.worktree_path.display().to_string(), None),
+        Column::Created => (time(task.created_at), None),
+        Column::Updated => (time(task.updated_at), None),
+        Column::Switched => (time(task.last_switched_at), None),
+    }
+}
+
+fn header(column: Column) -> &'static str {
+    match column {
+        Column::Project => "PROJECT",
+        Column::Title => "TITLE",
+        Column::Status => "STATUS",
+        Column::Reference => "REFERENCE",
+        Column::Description => "DESCRIPTION",
+        Column::Branch => "BRANCH",
+        Column::Worktree => "WORKTREE",
+        Column::Path => "WORKTREE PATH",
+        Column::Created => "CREATED",
+        Column::Updated => "UPDATED",
+        Column::Switched => "SWITCHED",
+    }
+}
+
+fn print_table(rows: &[(&str, &Task, Option<Health>)], columns: &[Column], levels: &Levels) {
+    let cells: Vec<Vec<(String, Option<Color>)>> = rows
+        .iter()
+        .map(|(project, task, health)| {
+            columns
+                .iter()
+                .map(|&column| cell(column, project, task, health.as_ref(), levels))
+                .collect()
+        })
+        .collect();
+
+    // Calculate column widths based on content, plus some padding
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, &column)| {
+            let widest = cells
+                .iter()
+                .map(|row| row[i].0.chars().count())
+                .max()
+                .unwrap_or(0);
+            widest.max(header(column).len()) + 2
+        })
+        .collect();
+
+    // The last column isn't padded, so lines don't end in spaces
+    let last = columns.len() - 1;
+    let line = |texts: Vec<ColoredString>| {
+        let line: String = texts.iter().map(|t| t.to_string()).collect();
+        println!("{}", line);
+    };
+
+    line(
+        columns
+            .iter()
+            .enumerate()
+            .map(|(i, &column)| match i {
+                i if i == last => header(column).bold(),
+                _ => format!("{:<w$}", header(column), w = widths[i]).bold(),
+            })
+            .collect(),
+    );
+
+    for row in cells {
+        line(
+            row.into_iter()
+                .enumerate()
+                .map(|(i, (text, color))| {
+                    // Pad before coloring so escape codes don't break alignment
+                    let text = match i {
+                        i if i == last => text,
+                        _ => format!("{:<w$}", text, w = widths[i]),
+                    };
+                    match color {
+                        Some(color) => text.color(color),
+                        None => text.normal(),
+                    }
+                })
+                .collect(),
+        );
+    }
+}
+
+fn print_simple(rows: &[(&str, &Task, Option<Health>)]) {
+    for (project, task, _) in rows {
+        println!("{}/{}", project, task.title);
+    }
+}
+
+fn print_json(rows: &[(&str, &Task, Option<Health>)], status: bool) -> TmResult<()> {
+    use serde_json::json;
+
+    let json_tasks: Vec<_> = rows
+        .iter()
+        .map(|(project, task, health)| {
+            let mut value = json!({
+                "project": project,
+                "title": task.title,
+                "status": task.status,
+                "worktree_path": task.worktree_path,
+                "description": task.description,
+                "reference": task.reference,
+                "remote_url": task.remote_url,
+                "api_url": task.api_url,
+                "created_at": task.created_at,
+                "updated_at": task.updated_at,
+                "last_switched_at": task.last_switched_at,
+            });
+            if let (true, Some(health)) = (status, health) {
+                value["worktree"] = health.to_json();
+            }
+            value
+        })
+        .collect();
+
+    println!("{}", serde_json::to_string_pretty(&json_tasks)?);
+
+    Ok(())
+}
+
+fn list_archived(
+    storage: &TaskStorage,
+    filters: &Filters,
+    path: Option<&Path>,
+    format: OutputFormat,
+    sort: Option<SortKey>,
+) -> TmResult<()> {
+    let mut tasks: Vec<(&str, &ArchivedTask)> = storage
+        .list_archived(None)
+        .into_iter()
+        .filter(|(project, archived)| filters.matches(project, &archived.task, path))
+        .collect();
+
+    if tasks.is_empty() {
+        println!("No archived tasks found.");
+        return Ok(());
+    }
+
+    // Most recently archived first, unless sorted otherwise
+    match sort {
+        Some(sort) => tasks.sort_by(|a, b| compare((a.0, &a.1.task), (b.0, &b.1.task), sort)),
+        None => tasks.sort_by_key(|(_, archived)| std::cmp::Reverse(archived.archived_at)),
+    }
+
+    match format {
+        OutputFormat::Table => print_archived_table(&tasks),
+        OutputFormat::Simple => {
+            for (project, archived) in &tasks {
+                println!("{}/{}", project, archived.task.title);
+            }
+        }
+        OutputFormat::Json => {
+            let json_tasks: Vec<_> = tasks
+                .iter()
+                .map(|(project, archived)| {
+                    serde_json::json!({
+                        "project": project,
+                        "title": archived.task.title,
+                        "status": archived.task.status,
+                        "worktree_path": archived.task.worktree_path,
+                        "description": archived.task.description,
+                        "reference": archived.task.reference,
+                        "archived_at": archived.archived_at,
+                        "branch": archived.branch,
+                        "commit": archived.commit,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&json_tasks)?);
+        }
+    }
+
+    Ok(())
+}
+
+fn print_archived_table(tasks: &[(&str, &ArchivedTask)]) {
     let mut max_project = "PROJECT".len();
     let mut max_title = "TITLE".len();
     let mut max_reference = "REFERENCE".len();
+    let max_archived = "YYYY-MM-DD HH:MM".len() + 2;
+    let mut max_branch = "BRANCH".len();
 
-    for (project, task) in tasks {
+    for (project, archived) in tasks {
         max_project = max_project.max(project.len());
-        max_title = max_title.max(task.title.len());
-        if let Some(ref r) = task.reference {
+        max_title = max_title.max(archived.task.title.len());
+        if let Some(ref r) = archived.task.reference {
             max_reference = max_reference.max(r.len());
         }
+        if let Some(ref b) = archived.branch {
+            max_branch = max_branch.max(b.len());
+        }
     }
 
-    // Add some padding
     max_project += 2;
     max_title += 2;
     max_reference += 2;
+    max_branch += 2;
 
-    // Header
     println!(
-        "{:<project_w$}{:<title_w$}{:<ref_w$}{}",
+        "{:<project_w$}{:<title_w$}{:<ref_w$}{:<archived_w$}{:<branch_w$}{}",
         "PROJECT".bold(),
         "TITLE".bold(),
         "REFERENCE".bold(),
-        "WORKTREE PATH".bold(),
+        "ARCHIVED".bold(),
+        "BRANCH".bold(),
+        "COMMIT".bold(),
         project_w = max_project,
         title_w = max_title,
         ref_w = max_reference,
+        archived_w = max_archived,
+        branch_w = max_branch,
     );
 
-    // Tasks
-    for (project, task) in tasks {
+    for (project, archived) in tasks {
+        let commit = archived.commit.as_deref().unwrap_or("-");
         println!(
-            "{:<project_w$}{:<title_w$}{:<ref_w$}{}",
+            "{:<project_w$}{:<title_w$}{:<ref_w$}{:<archived_w$}{:<branch_w$}{}",
             project,
-            task.title,
-            task.reference.as_deref().unwrap_or("-"),
-            task.worktree_path.display(),
+            archived.task.title,
+            archived.task.reference.as_deref().unwrap_or("-"),
+            archived
+                .archived_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+            archived.branch.as_deref().unwrap_or("-"),
+            short_commit(commit),
             project_w = max_project,
             title_w = max_title,
             ref_w = max_reference,
+            archived_w = max_archived,
+            branch_w = max_branch,
         );
     }
 }
 
-fn print_simple(tasks: &[(&str, &crate::models::task::Task)]) {
-    for (project, task) in tasks {
-        println!("{}/{}", project, task.title);
-    }
-}
-
-fn print_json(tasks: &[(&str, &crate::models::task::Task)]) -> TmResult<()> {
-    use serde_json::json;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let json_tasks: Vec<_> = tasks
-        .iter()
-        .map(|(project, task)| {
-            json!({
-                "project": project,
-                "title": task.title,
-                "worktree_path": task.worktree_path,
-                "description": task.description,
-                "reference": task.reference,
-                "remote_url": task.remote_url,
-                "api_url": task.api_url,
-            })
-        })
-        .collect();
-
-    println!("{}", serde_json::to_string_pretty(&json_tasks)?);
-
-    Ok(())
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("JIRA-1", "jira-1"));
+        assert!(!matches_pattern("JIRA-1", "JIRA-12"));
+        assert!(matches_pattern("jira-*", "JIRA-123"));
+        assert!(matches_pattern("*-12?", "JIRA-123"));
+        assert!(matches_pattern("*a*b*", "xaxxbx"));
+        assert!(!matches_pattern("*a*b", "xaxxbx"));
+        assert!(matches_pattern("*", ""));
+    }
 }
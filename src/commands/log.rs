@@ -0,0 +1,140 @@
+use crate::config::{get_history_file_path, get_tasks_file_path};
+use crate::error::{TmError, TmResult};
+use crate::models::history::{self, Event, EventKind};
+use crate::models::selector::TaskSelector;
+use crate::models::storage::TaskStorage;
+use chrono::{DateTime, Local, Utc};
+
+/// One of the names a task had, up to the time it was renamed
+struct Name {
+    project: String,
+    title: String,
+    until: Option<DateTime<Utc>>,
+}
+
+/// Which events to show
+enum Filter {
+    All,
+    /// A task under each of the names it had, newest first
+    Task {
+        names: Vec<Name>,
+    },
+}
+
+impl Filter {
+    fn task(project: String, title: String) -> Self {
+        Filter::Task {
+            names: vec![Name {
+                project,
+                title,
+                until: None,
+            }],
+        }
+    }
+
+    fn matches(&self, event: &Event) -> bool {
+        match self {
+            Filter::All => true,
+            Filter::Task { names } => names.iter().any(|name| {
+                event.project == name.project
+                    && event.title == name.title
+                    && name.until.is_none_or(|until| event.at <= until)
+            }),
+        }
+    }
+
+    /// Follow renames and moves back, so the task's events from before are shown too
+    fn with_earlier_names(mut self, events: &[Event]) -> Self {
+        if let Filter::Task { names } = &mut self {
+            for event in events.iter().rev() {
+                let current = &names[names.len() - 1];
+                if event.project != current.project || event.title != current.title {
+                    continue;
+                }
+                let (project, title) = match &event.kind {
+                    EventKind::Renamed { from } => (current.project.clone(), from.clone()),
+                    EventKind::Moved { from } => (from.clone(), current.title.clone()),
+                    _ => continue,
+                };
+                names.push(Name {
+                    project,
+                    title,
+                    until: Some(event.at),
+                });
+            }
+        }
+        self
+    }
+}
+
+/// Resolve the selector against active tasks, then archived ones, then the titles in the
+/// history itself, since removed tasks only live on there
+fn filter_for(selector: TaskSelector, events: &[Event]) -> TmResult<Filter> {
+    let tasks_file = get_tasks_file_path()?;
+    let storage = TaskStorage::load(&tasks_file)?;
+
+    let error = match storage.resolve(&selector) {
+        Ok((project, title)) => return Ok(Filter::task(project, title)),
+        Err(e) => e,
+    };
+    if let Ok((project, title)) = storage.resolve_archived(&selector) {
+        return Ok(Filter::task(project, title));
+    }
+
+    let found = events.iter().find(|event| match &selector {
+        TaskSelector::InProject { project, query } => {
+            event.project == *project && event.title == *query
+        }
+        TaskSelector::Query(query) => {
+            event.title == *query || format!("{}/{}", event.project, event.title) == *query
+        }
+        TaskSelector::Current => false,
+    });
+
+    match (found, error) {
+        (Some(event), _) => Ok(Filter::task(event.project.clone(), event.title.clone())),
+        // Outside any worktree, show everything rather than fail
+        (None, TmError::NotInTask { .. }) => Ok(Filter::All),
+        (None, error) => Err(error),
+    }
+}
+
+pub fn execute(
+    selector: TaskSelector,
+    all: bool,
+    limit: Option<usize>,
+    json: bool,
+) -> TmResult<()> {
+    let events = history::load(&get_history_file_path()?)?;
+
+    let filter = if all {
+        Filter::All
+    } else {
+        filter_for(selector, &events)?.with_earlier_names(&events)
+    };
+
+    let mut events: Vec<&Event> = events.iter().filter(|e| filter.matches(e)).collect();
+    if let Some(limit) = limit {
+        events.drain(..events.len().saturating_sub(limit));
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&events)?);
+        return Ok(());
+    }
+
+    if events.is_empty() {
+        println!("No history found.");
+        return Ok(());
+    }
+
+    for event in events {
+        let at = event.at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S");
+        match filter {
+            Filter::All => println!("{}  {}/{}  {}", at, event.project, event.title, event.kind),
+            Filter::Task { .. } => println!("{}  {}", at, event.kind),
+        }
+    }
+
+    Ok(())
+}
@@ -1,4 +1,21 @@
 pub mod add;
+pub mod archive;
+pub mod complete;
+pub mod current;
+pub mod doctor;
+pub mod edit;
+pub mod import;
+pub mod init;
 pub mod list;
+pub mod log;
+pub mod mv;
+pub mod project;
+pub mod prune;
 pub mod remove;
+pub mod rename;
+pub mod restore;
+pub mod review;
+pub mod show;
+pub mod status;
 pub mod switch;
+pub mod ui;
@@ -0,0 +1,45 @@
+use crate::config::get_tasks_file_path;
+use crate::error::{TmError, TmResult};
+use crate::models::history::{self, EventKind};
+use crate::models::selector::TaskSelector;
+use crate::models::storage::TaskStorage;
+
+/// Move a task to another project, leaving its worktree and branch untouched
+pub fn execute(from_project: String, title: String, to_project: String) -> TmResult<()> {
+    if from_project == to_project {
+        return Err(TmError::InvalidInput {
+            field: "project".to_string(),
+            reason: format!("Task is already in project '{}'", to_project),
+        });
+    }
+
+    let tasks_file = get_tasks_file_path()?;
+    let (mut storage, _lock) = TaskStorage::load_locked(&tasks_file)?;
+    let (project, title) = storage.resolve(&TaskSelector::InProject {
+        project: from_project,
+        query: title,
+    })?;
+
+    storage.move_task(&project, &title, &to_project)?;
+
+    // The target project's main repository may differ, so pin down the task's own
+    let task = storage.get_task_mut(&to_project, &title)?;
+    task.main_repo_path = task.main_repo();
+    task.touch();
+
+    storage.save(&tasks_file)?;
+    history::record(
+        &to_project,
+        &title,
+        EventKind::Moved {
+            from: project.clone(),
+        },
+    );
+
+    println!(
+        "Moved task '{}' from project '{}' to '{}'",
+        title, project, to_project
+    );
+
+    Ok(())
+}
@@ -0,0 +1,222 @@
+use crate::config::{get_tasks_file_path, Config, REPO_CONFIG_FILE};
+use crate::error::{TmError, TmResult};
+use crate::git;
+use crate::level::{Level, DEFAULT_LEVEL};
+use crate::models::history::{self, EventKind};
+use crate::models::storage::TaskStorage;
+use crate::template::Template;
+use colored::Colorize;
+use std::path::PathBuf;
+
+#[allow(clippy::too_many_arguments)]
+pub fn add(
+    name: String,
+    main_repo: Option<PathBuf>,
+    base_branch: Option<String>,
+    level: Option<Level>,
+    worktree_root: Option<PathBuf>,
+    branch_template: Option<String>,
+    worktree_template: Option<String>,
+    post_create: Vec<String>,
+    pre_remove: Vec<String>,
+) -> TmResult<()> {
+    let mut config = Config::load()?;
+    let exists = config.projects.contains_key(&name);
+
+    if !exists && main_repo.is_none() {
+        return Err(TmError::MissingMainRepo { project: name });
+    }
+
+    let project = config.projects.entry(name.clone()).or_default();
+
+    if let Some(path) = main_repo {
+        git::validate_worktree(&path)?;
+        project.main_repo = Some(std::path::absolute(path)?);
+    }
+    if let Some(base) = base_branch {
+        project.base_branch = Some(base);
+    }
+    if let Some(level) = level {
+        project.level = Some(level.as_str().to_string());
+    }
+    if let Some(root) = worktree_root {
+        project.worktree_root = Some(std::path::absolute(root)?);
+    }
+    if let Some(template) = branch_template {
+        Template::parse_branch(&template).map_err(|reason| TmError::InvalidInput {
+            field: "branch template".to_string(),
+            reason,
+        })?;
+        project.branch_template = Some(template);
+    }
+    if let Some(template) = worktree_template {
+        Template::parse_worktree(&template).map_err(|reason| TmError::InvalidInput {
+            field: "worktree template".to_string(),
+            reason,
+        })?;
+        project.worktree_template = Some(template);
+    }
+    if !post_create.is_empty() {
+        project.hooks.post_create = post_create;
+    }
+    if !pre_remove.is_empty() {
+        project.hooks.pre_remove = pre_remove;
+    }
+
+    config.save()?;
+
+    if exists {
+        println!("Updated project '{}'", name);
+    } else {
+        println!("Added project '{}'", name);
+    }
+
+    Ok(())
+}
+
+pub fn list() -> TmResult<()> {
+    let config = Config::load()?;
+
+    if config.projects.is_empty() {
+        println!("No projects configured. Use 'tm project add' to add one.");
+        return Ok(());
+    }
+
+    let max_name = config
+        .projects
+        .keys()
+        .map(|n| n.len())
+        .max()
+        .unwrap_or(0)
+        .max("PROJECT".len())
+        + 2;
+
+    println!(
+        "{:<name_w$}{}",
+        "PROJECT".bold(),
+        "MAIN REPOSITORY".bold(),
+        name_w = max_name
+    );
+    for (name, project) in &config.projects {
+        println!(
+            "{:<name_w$}{}",
+            name,
+            project
+                .main_repo
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            name_w = max_name
+        );
+    }
+
+    Ok(())
+}
+
+pub fn show(name: String) -> TmResult<()> {
+    let config = Config::load()?;
+
+    if !config.projects.contains_key(&name) {
+        return Err(TmError::ProjectNotFound { project: name });
+    }
+
+    let settings = config.settings(&name, None)?;
+    let repo_config = settings.main_repo.join(REPO_CONFIG_FILE);
+
+    println!("{}", name.bold());
+    println!("  main repository: {}", settings.main_repo.display());
+    println!(
+        "  base branch:     {}",
+        settings.base_branch.as_deref().unwrap_or("HEAD")
+    );
+    println!(
+        "  level:           {}",
+        settings
+            .level
+            .as_ref()
+            .map_or(DEFAULT_LEVEL, |level| level.as_str())
+    );
+    println!("  worktree root:   {}", settings.worktree_root.display());
+    println!("  branch template: {}", settings.branch_template.as_str());
+    println!("  worktree path:   {}", settings.worktree_template.as_str());
+    if repo_config.exists() {
+        println!("  repo config:     {}", repo_config.display());
+    }
+
+    if !settings.hooks.post_create.is_empty() {
+        println!("  post-create hooks:");
+        for hook in &settings.hooks.post_create {
+            println!("    {}", hook);
+        }
+    }
+    if !settings.hooks.pre_remove.is_empty() {
+        println!("  pre-remove hooks:");
+        for hook in &settings.hooks.pre_remove {
+            println!("    {}", hook);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn remove(name: String) -> TmResult<()> {
+    let mut config = Config::load()?;
+
+    if config.projects.remove(&name).is_none() {
+        return Err(TmError::ProjectNotFound { project: name });
+    }
+
+    config.save()?;
+
+    println!("Removed configuration of project '{}'", name);
+
+    Ok(())
+}
+
+/// Rename a project, re-keying its tasks and configuration
+/// Worktrees and branches are left as they are
+pub fn rename(name: String, new_name: String) -> TmResult<()> {
+    if name == new_name {
+        return Err(TmError::InvalidInput {
+            field: "name".to_string(),
+            reason: "The new name is the same as the old one".to_string(),
+        });
+    }
+
+    let mut config = Config::load()?;
+    if config.projects.contains_key(&name) && config.projects.contains_key(&new_name) {
+        return Err(TmError::InvalidInput {
+            field: "name".to_string(),
+            reason: format!("Project '{}' is already configured", new_name),
+        });
+    }
+
+    let tasks_file = get_tasks_file_path()?;
+    let (mut storage, _lock) = TaskStorage::load_locked(&tasks_file)?;
+    let titles = storage.rename_project(&name, &new_name)?;
+    let project_config = config.projects.remove(&name);
+
+    if titles.is_empty() && project_config.is_none() {
+        return Err(TmError::ProjectNotFound { project: name });
+    }
+
+    if !titles.is_empty() {
+        storage.save(&tasks_file)?;
+        for title in &titles {
+            history::record(&new_name, title, EventKind::Moved { from: name.clone() });
+        }
+    }
+    if let Some(project_config) = project_config {
+        config.projects.insert(new_name.clone(), project_config);
+        config.save()?;
+    }
+
+    println!(
+        "Renamed project '{}' to '{}' ({} task(s))",
+        name,
+        new_name,
+        titles.len()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,213 @@
+use crate::config::get_tasks_file_path;
+use crate::error::{TmError, TmResult};
+use crate::git;
+use crate::hooks;
+use crate::models::history::{self, EventKind};
+use crate::models::storage::TaskStorage;
+use crate::models::task::Task;
+use crate::prompt;
+use chrono::Utc;
+use std::fmt;
+use std::path::PathBuf;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Why a task can be pruned
+enum Reason {
+    /// The branch is fully contained in its base
+    Merged { base: String },
+    /// Nothing happened for this many days
+    Stale { days: i64 },
+    /// The branch's upstream was deleted on the remote
+    Gone,
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reason::Merged { base } => write!(f, "merged into {}", base),
+            Reason::Stale { days } => write!(f, "no activity for {} days", days),
+            Reason::Gone => f.write_str("upstream branch is gone"),
+        }
+    }
+}
+
+struct Candidate {
+    project: String,
+    title: String,
+    worktree_path: PathBuf,
+    main_repo: Option<PathBuf>,
+    branch: Option<String>,
+    reasons: Vec<Reason>,
+}
+
+impl Candidate {
+    fn is_merged(&self) -> bool {
+        self.reasons
+            .iter()
+            .any(|r| matches!(r, Reason::Merged { .. }))
+    }
+}
+
+/// Latest sign of life: the last commit, switch or metadata change
+fn last_activity(task: &Task) -> Option<i64> {
+    [
+        git::head_commit_time(&task.worktree_path),
+        task.last_switched_at.map(|t| t.timestamp()),
+        task.updated_at.map(|t| t.timestamp()),
+    ]
+    .into_iter()
+    .flatten()
+    .max()
+}
+
+fn check(project: &str, task: &Task, stale_days: u32, gone: bool) -> Candidate {
+    let branch = git::current_branch(&task.worktree_path);
+    let main_repo = task.main_repo();
+
+    let mut reasons = Vec::new();
+
+    if let (Some(branch), Some(main_repo)) = (&branch, &main_repo) {
+        let start = task.work_start();
+        let base = task
+            .base_branch
+            .clone()
+            .or_else(|| git::current_branch(main_repo))
+            .filter(|base| base != branch);
+        if let Some(base) = base {
+            if git::is_merged(main_repo, branch, &base, start).unwrap_or(false) {
+                reasons.push(Reason::Merged { base });
+            }
+        }
+
+        if gone && git::upstream_gone(main_repo, branch, start) {
+            reasons.push(Reason::Gone);
+        }
+    }
+
+    if stale_days > 0 {
+        if let Some(activity) = last_activity(task) {
+            let days = (Utc::now().timestamp() - activity) / SECONDS_PER_DAY;
+            if days >= i64::from(stale_days) {
+                reasons.push(Reason::Stale { days });
+            }
+        }
+    }
+
+    Candidate {
+        project: project.to_string(),
+        title: task.title.clone(),
+        worktree_path: task.worktree_path.clone(),
+        main_repo,
+        branch,
+        reasons,
+    }
+}
+
+pub fn execute(
+    project: Option<String>,
+    stale_days: u32,
+    gone: bool,
+    yes: bool,
+    dry_run: bool,
+    force: bool,
+) -> TmResult<()> {
+    // Step 1: Find tasks that look finished or abandoned; the task file isn't locked
+    // until the user confirmed
+    let tasks_file = get_tasks_file_path()?;
+    let storage = TaskStorage::load(&tasks_file)?;
+    let mut tasks = storage.list_tasks(project.as_deref());
+    tasks.sort_by(|a, b| (a.0, &a.1.title).cmp(&(b.0, &b.1.title)));
+
+    let candidates: Vec<Candidate> = tasks
+        .into_iter()
+        .map(|(project, task)| check(project, task, stale_days, gone))
+        .filter(|c| !c.reasons.is_empty())
+        .collect();
+
+    if candidates.is_empty() {
+        println!("Nothing to prune.");
+        return Ok(());
+    }
+
+    // Step 2: Report and confirm
+    for c in &candidates {
+        let reasons: Vec<String> = c.reasons.iter().map(|r| r.to_string()).collect();
+        println!("{}/{}: {}", c.project, c.title, reasons.join(", "));
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+
+    let question = format!(
+        "Remove {} task(s) with their worktrees, and delete merged branches?",
+        candidates.len()
+    );
+    if !yes && !prompt::confirm(&question, false)? {
+        return Err(TmError::Cancelled);
+    }
+
+    // Step 3: Run pre-remove hooks without holding the task file lock, since they may
+    // run tm themselves; a dirty worktree or a failing hook keeps its task
+    let mut ready = Vec::new();
+    for c in &candidates {
+        if c.worktree_path.exists() {
+            let result = storage.get_task(&c.project, &c.title).and_then(|task| {
+                git::check_clean(&c.worktree_path, force)?;
+                hooks::run_pre_remove(&c.project, task)
+            });
+            if let Err(e) = result {
+                println!("Skipped '{}/{}': {}", c.project, c.title, e);
+                continue;
+            }
+        }
+        ready.push(c);
+    }
+
+    // Step 4: Remove worktrees first, leaving tasks changed since they were listed alone
+    let (mut storage, _lock) = TaskStorage::load_locked(&tasks_file)?;
+    let mut removed = Vec::new();
+    for c in ready {
+        let unchanged = storage
+            .get_task(&c.project, &c.title)
+            .is_ok_and(|task| task.worktree_path == c.worktree_path);
+        if !unchanged {
+            println!(
+                "Skipped '{}/{}': changed in the meantime",
+                c.project, c.title
+            );
+            continue;
+        }
+
+        if c.worktree_path.exists() {
+            if let Err(e) = git::remove_worktree(&c.worktree_path, force) {
+                println!("Skipped '{}/{}': {}", c.project, c.title, e);
+                continue;
+            }
+            println!("Removed worktree at: {}", c.worktree_path.display());
+        }
+
+        storage.remove_task(&c.project, &c.title)?;
+        println!("Removed task '{}' from project '{}'", c.title, c.project);
+        removed.push(c);
+
+        if let (true, Some(branch), Some(main_repo)) = (c.is_merged(), &c.branch, &c.main_repo) {
+            match git::delete_branch(main_repo, branch) {
+                Ok(()) => println!("Deleted merged branch {}", branch),
+                Err(e) => println!("Could not delete branch {}: {}", branch, e),
+            }
+        }
+    }
+
+    if removed.is_empty() {
+        return Ok(());
+    }
+
+    storage.save(&tasks_file)?;
+    for c in removed {
+        history::record(&c.project, &c.title, EventKind::Removed);
+    }
+
+    Ok(())
+}
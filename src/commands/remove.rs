@@ -1,31 +1,42 @@
 use crate::config::get_tasks_file_path;
 use crate::error::TmResult;
-use crate::git::remove_worktree;
-use crate::models::storage::TaskStorage;
-
-pub fn execute(
-    project: String,
-    title: String,
-    remove_worktree_flag: bool,
-    force: bool,
-) -> TmResult<()> {
+use crate::git;
+use crate::hooks;
+use crate::models::history::{self, EventKind};
+use crate::models::{selector::TaskSelector, storage::TaskStorage};
+
+pub fn execute(selector: TaskSelector, remove_worktree_flag: bool, force: bool) -> TmResult<()> {
     let tasks_file = get_tasks_file_path()?;
-    let mut storage = TaskStorage::load(&tasks_file)?;
+    let storage = TaskStorage::load(&tasks_file)?;
+    let (project, title) = storage.resolve(&selector)?;
+    let task = storage.get_task(&project, &title)?;
+
+    // Refuse a dirty worktree before running pre-remove hooks, and run them without
+    // holding the task file lock, since they may run tm themselves
+    let remove = remove_worktree_flag && task.worktree_path.exists();
+    if remove {
+        git::check_clean(&task.worktree_path, force)?;
+        hooks::run_pre_remove(&project, task)?;
+    }
 
-    // Get the task before removing it (to get worktree path)
+    // Reload under the lock, the hooks may have changed the task
+    let (mut storage, _lock) = TaskStorage::load_locked(&tasks_file)?;
     let task = storage.get_task(&project, &title)?.clone();
 
+    // Remove the worktree before touching storage, so a failure aborts cleanly
+    if remove {
+        git::remove_worktree(&task.worktree_path, force)?;
+    }
+
     // Remove from storage
     storage.remove_task(&project, &title)?;
 
     // Save storage
     storage.save(&tasks_file)?;
+    history::record(&project, &title, EventKind::Removed);
 
     println!("Removed task '{}' from project '{}'", title, project);
-
-    // Optionally remove worktree
-    if remove_worktree_flag {
-        remove_worktree(&task.worktree_path, force)?;
+    if remove {
         println!("Removed worktree at: {}", task.worktree_path.display());
     }
 
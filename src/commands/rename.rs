@@ -0,0 +1,289 @@
+use crate::commands::add::validate_inputs;
+use crate::config::{get_tasks_file_path, Config};
+use crate::error::{TmError, TmResult};
+use crate::git;
+use crate::level::{Level, Levels};
+use crate::models::history::{self, EventKind};
+use crate::models::selector::TaskSelector;
+use crate::models::storage::TaskStorage;
+use crate::template::TemplateVars;
+use std::path::PathBuf;
+
+/// Git changes made by `tm rename`, undone in reverse order if a later step fails
+#[derive(Default)]
+struct Rollback {
+    /// Branch renamed from the first name to the second, with its repository
+    branch: Option<(PathBuf, String, String)>,
+    /// Worktree moved from the first path to the second
+    worktree: Option<(PathBuf, PathBuf)>,
+}
+
+impl Rollback {
+    fn undo(self) {
+        if let Some((old_path, new_path)) = self.worktree {
+            match git::move_worktree(&new_path, &old_path) {
+                Ok(()) => eprintln!("Rolled back: moved worktree back to {}", old_path.display()),
+                Err(e) => eprintln!(
+                    "Could not move worktree back to {}: {}",
+                    old_path.display(),
+                    e
+                ),
+            }
+        }
+
+        if let Some((main_repo_path, old_name, new_name)) = self.branch {
+            match git::rename_branch(&main_repo_path, &new_name, &old_name) {
+                Ok(()) => eprintln!("Rolled back: renamed branch back to {}", old_name),
+                Err(e) => eprintln!("Could not rename branch back to {}: {}", old_name, e),
+            }
+        }
+    }
+}
+
+/// Split a title into its level, ID and name
+/// The stored reference marks where the ID ends, since IDs and names may both contain
+/// digits; titles whose ID isn't the reference fall back to `parse_branch_name`
+fn split_title(
+    title: &str,
+    reference: Option<&str>,
+    levels: &Levels,
+) -> Option<(Level, String, String)> {
+    let (level, rest) = title.split_once('/')?;
+    let level = levels
+        .find(level)
+        .or_else(|| levels.by_prefix(level))?
+        .clone();
+
+    let name = reference
+        .and_then(|reference| rest.strip_prefix(reference))
+        .and_then(|name| name.strip_prefix('-'))
+        .filter(|name| !name.is_empty() && !name.contains('/'));
+    match (reference, name) {
+        (Some(reference), Some(name)) => Some((level, reference.to_string(), name.to_string())),
+        _ => {
+            let c = git::parse_branch_name(title)?;
+            Some((level, c.id, c.name))
+        }
+    }
+}
+
+pub fn execute(
+    selector: TaskSelector,
+    level: Option<Level>,
+    id: Option<String>,
+    name: Option<String>,
+    force: bool,
+    keep_worktree: bool,
+) -> TmResult<()> {
+    if level.is_none() && id.is_none() && name.is_none() {
+        return Err(TmError::InvalidInput {
+            field: "level/id/name".to_string(),
+            reason: "Give at least one of --level, --id or --name".to_string(),
+        });
+    }
+
+    let tasks_file = get_tasks_file_path()?;
+    let (mut storage, _lock) = TaskStorage::load_locked(&tasks_file)?;
+    let (project, title) = storage.resolve(&selector)?;
+    let task = storage.get_task(&project, &title)?.clone();
+
+    // Step 1: Take the parts that don't change from the current title
+    let config = Config::load()?;
+    let levels = config.levels()?;
+    let missing = |field: &str| TmError::InvalidInput {
+        field: field.to_string(),
+        reason: format!(
+            "'{}' doesn't follow the <level>/<id>-<name> scheme, so --{} is required",
+            title, field
+        ),
+    };
+    let current = split_title(&title, task.reference.as_deref(), &levels);
+    let (old_level, old_id, old_name) = match current {
+        Some(parts) => parts,
+        None => {
+            let level = level.clone().ok_or_else(|| missing("level"))?;
+            let id = id.clone().ok_or_else(|| missing("id"))?;
+            let name = name.clone().ok_or_else(|| missing("name"))?;
+            (level, id, name)
+        }
+    };
+    let new_level = level.unwrap_or_else(|| old_level.clone());
+    let new_id = id.unwrap_or_else(|| old_id.clone());
+    let new_name = name.unwrap_or_else(|| old_name.clone());
+    validate_inputs(&new_id, &new_name)?;
+
+    let new_title = git::generate_branch_name(new_level.as_str(), &new_id, &new_name);
+    if new_title == title {
+        println!("Task '{}' already has that name", title);
+        return Ok(());
+    }
+    if storage.get_task(&project, &new_title).is_ok() {
+        return Err(TmError::DuplicateTask {
+            project,
+            title: new_title,
+        });
+    }
+    if storage.get_archived(&project, &new_title).is_ok() {
+        return Err(TmError::DuplicateArchivedTask {
+            project,
+            title: new_title,
+        });
+    }
+
+    // Step 2: Compute the old and new branch and worktree names from the project's templates
+    let settings = config.settings(&project, task.main_repo_path.clone())?;
+    let main_repo_path = settings.main_repo;
+    let render = |level: &Level, id: &str, name: &str| {
+        let vars = TemplateVars {
+            user: &settings.user,
+            project: &project,
+            level: level.dir(),
+            id,
+            name,
+        };
+        let worktree_path = settings
+            .worktree_root
+            .join(settings.worktree_template.render(&vars));
+        let branch = settings.branch_template.render(&TemplateVars {
+            level: level.prefix(),
+            ..vars
+        });
+        (worktree_path, branch)
+    };
+    let (old_template_path, old_branch) = render(&old_level, &old_id, &old_name);
+    let (new_template_path, new_branch) = render(&new_level, &new_id, &new_name);
+
+    // Branches checked out with --from-branch keep their names; a worktree that isn't
+    // where the template puts it is only left alone when asked to
+    let branch = git::current_branch(&task.worktree_path);
+    let rename_branch = branch.as_deref() == Some(old_branch.as_str()) && new_branch != old_branch;
+    let at_template = git::same_path(&task.worktree_path, &old_template_path);
+    if !at_template && !keep_worktree {
+        return Err(TmError::InvalidInput {
+            field: "worktree".to_string(),
+            reason: format!(
+                "'{}' is not where the project's worktree template puts '{}' ({}); \
+                 use --keep-worktree to rename without moving it",
+                task.worktree_path.display(),
+                title,
+                old_template_path.display()
+            ),
+        });
+    }
+    let move_worktree = at_template && !keep_worktree && new_template_path != old_template_path;
+
+    if rename_branch && !git::is_valid_branch_name(&new_branch) {
+        return Err(TmError::InvalidInput {
+            field: "id/name".to_string(),
+            reason: format!("'{}' is not a valid branch name", new_branch),
+        });
+    }
+    if move_worktree && new_template_path.exists() {
+        return Err(TmError::WorktreeAlreadyExists {
+            path: new_template_path,
+        });
+    }
+
+    // Step 3: Refuse to move uncommitted work unless forced
+    git::validate_worktree(&task.worktree_path)?;
+    git::check_clean(&task.worktree_path, force)?;
+
+    // Step 4: Rename the branch, move the worktree and save the task as one transaction
+    let mut rollback = Rollback::default();
+    let mut renamed = task.clone();
+    renamed.title = new_title.clone();
+    if task.reference.is_none() || task.reference.as_deref() == Some(old_id.as_str()) {
+        renamed.reference = Some(new_id);
+    }
+    renamed.touch();
+
+    let result = (|| -> TmResult<()> {
+        if rename_branch {
+            git::rename_branch(&main_repo_path, &old_branch, &new_branch)?;
+            rollback.branch = Some((
+                main_repo_path.clone(),
+                old_branch.clone(),
+                new_branch.clone(),
+            ));
+        }
+
+        if move_worktree {
+            git::move_worktree(&task.worktree_path, &new_template_path)?;
+            rollback.worktree = Some((task.worktree_path.clone(), new_template_path.clone()));
+            renamed.worktree_path = new_template_path.clone();
+        }
+
+        storage.remove_task(&project, &title)?;
+        storage.add_task(project.clone(), renamed.clone())?;
+        storage.save(&tasks_file)
+    })();
+
+    if let Err(e) = result {
+        rollback.undo();
+        return Err(e);
+    }
+    // Only removes the old parent directory if the worktree was the last thing in it
+    if let Some(parent) = task.worktree_path.parent().filter(|_| move_worktree) {
+        let _ = std::fs::remove_dir(parent);
+    }
+
+    history::record(
+        &project,
+        &new_title,
+        EventKind::Renamed {
+            from: title.clone(),
+        },
+    );
+
+    println!("Renamed task '{}' to '{}'", title, new_title);
+    if rename_branch {
+        println!("Branch: {} -> {}", old_branch, new_branch);
+    } else if let Some(branch) = branch {
+        println!("Kept branch {}", branch);
+    }
+    if move_worktree {
+        println!("Moved worktree to: {}", renamed.worktree_path.display());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_title_with_digits() {
+        let levels = Levels::builtin();
+        let split = |title: &str, reference: Option<&str>| {
+            split_title(title, reference, &levels)
+                .map(|(level, id, name)| (level.as_str().to_string(), id, name))
+        };
+        let parts = |level: &str, id: &str, name: &str| {
+            Some((level.to_string(), id.to_string(), name.to_string()))
+        };
+
+        assert_eq!(
+            split("feature/ABC-2fa-login", Some("ABC")),
+            parts("feature", "ABC", "2fa-login")
+        );
+        assert_eq!(
+            split("fix/JIRA-123-oauth2-v2", Some("JIRA-123")),
+            parts("fix", "JIRA-123", "oauth2-v2")
+        );
+        assert_eq!(
+            split("feature/83772-nem-plonn", Some("83772")),
+            parts("feature", "83772", "nem-plonn")
+        );
+        // Without a matching reference the ID ends at the first segment with a digit
+        assert_eq!(
+            split("feature/JIRA-123-auth", Some("OTHER-1")),
+            parts("feature", "JIRA-123", "auth")
+        );
+        assert_eq!(
+            split("feature/JIRA-123-auth", None),
+            parts("feature", "JIRA-123", "auth")
+        );
+        assert_eq!(split("nolevel/ABC-2fa-login", Some("ABC")), None);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::config::get_tasks_file_path;
+use crate::error::{TmError, TmResult};
+use crate::format::format_age;
+use crate::models::storage::{backup_path, StorageLock, TaskStorage, BACKUP_COUNT};
+
+pub fn execute(backup: usize, list: bool) -> TmResult<()> {
+    let tasks_file = get_tasks_file_path()?;
+
+    if list {
+        let mut found = false;
+        for n in 1..=BACKUP_COUNT {
+            let path = backup_path(&tasks_file, n);
+            let Ok(metadata) = std::fs::metadata(&path) else {
+                continue;
+            };
+            found = true;
+
+            let age = metadata
+                .modified()
+                .map(format_age)
+                .unwrap_or_else(|_| "unknown age".to_string());
+            let summary = match TaskStorage::load(&path) {
+                Ok(storage) => format!("{} task(s)", storage.list_tasks(None).len()),
+                Err(_) => "unreadable".to_string(),
+            };
+
+            println!("{}  {}  {}  {}", n, age, summary, path.display());
+        }
+
+        if !found {
+            println!("No backups found.");
+        }
+        return Ok(());
+    }
+
+    if !(1..=BACKUP_COUNT).contains(&backup) {
+        return Err(TmError::InvalidInput {
+            field: "backup".to_string(),
+            reason: format!("must be between 1 and {}", BACKUP_COUNT),
+        });
+    }
+
+    let _lock = StorageLock::acquire(&tasks_file)?;
+
+    let path = backup_path(&tasks_file, backup);
+    if !path.exists() {
+        return Err(TmError::InvalidInput {
+            field: "backup".to_string(),
+            reason: format!("no backup found at {}", path.display()),
+        });
+    }
+
+    // Only restore backups that parse, saving keeps the current file as backup 1
+    let storage = TaskStorage::load(&path)?;
+    storage.save(&tasks_file)?;
+
+    println!(
+        "Restored {} task(s) from {}",
+        storage.list_tasks(None).len(),
+        path.display()
+    );
+
+    Ok(())
+}
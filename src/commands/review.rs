@@ -0,0 +1,77 @@
+use crate::commands::add::{self, BranchSource, NewTask};
+use crate::config::Config;
+use crate::error::TmResult;
+use crate::git;
+use crate::models::task::Task;
+use crate::template::TemplateVars;
+use std::path::PathBuf;
+
+pub fn execute(
+    project: String,
+    branch: String,
+    main_repo_path: Option<PathBuf>,
+    description: Option<String>,
+    fetch: bool,
+) -> TmResult<()> {
+    // Step 1: Fill in the main repository from the project configuration
+    let config = Config::load()?;
+    let settings = config.settings(&project, main_repo_path)?;
+    let main_repo_path = settings.main_repo;
+
+    // Step 2: Validate main repo path is a git repository
+    git::validate_worktree(&main_repo_path)?;
+
+    // Step 3: Derive title and worktree path under the review level
+    // Branches following the naming scheme keep their id and name,
+    // e.g. "feature/JIRA-123-auth" -> "review/JIRA-123-auth"
+    let levels = config.levels()?;
+    let level = levels.review();
+    let (task_title, worktree_path, reference) = match git::parse_branch_name(&branch) {
+        Some(c) => {
+            let vars = TemplateVars {
+                user: &settings.user,
+                project: &project,
+                level: level.dir(),
+                id: &c.id,
+                name: &c.name,
+            };
+            (
+                git::generate_branch_name(level.as_str(), &c.id, &c.name),
+                settings
+                    .worktree_root
+                    .join(settings.worktree_template.render(&vars)),
+                Some(c.id),
+            )
+        }
+        None => {
+            let flat = branch.replace('/', "-");
+            (
+                format!("{}/{}", level.as_str(), git::to_kebab_case(&flat)),
+                settings
+                    .worktree_root
+                    .join(level.dir())
+                    .join(git::to_snake_case(&flat)),
+                None,
+            )
+        }
+    };
+
+    // Step 4: Create task with builder pattern
+    let mut task = Task::new(task_title, worktree_path).with_main_repo_path(main_repo_path.clone());
+
+    if let Some(reference) = reference {
+        task = task.with_reference(reference);
+    }
+    if let Some(desc) = description {
+        task = task.with_description(desc);
+    }
+
+    add::create(NewTask {
+        project,
+        main_repo_path,
+        branch: BranchSource::Existing(branch),
+        fetch,
+        post_create: settings.hooks.post_create,
+        task,
+    })
+}
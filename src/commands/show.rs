@@ -0,0 +1,173 @@
+use crate::commands::list::Health;
+use crate::config::get_tasks_file_path;
+use crate::error::TmResult;
+use crate::format::{format_age, short_commit};
+use crate::git::{self, WorktreeInfo};
+use crate::models::selector::TaskSelector;
+use crate::models::storage::TaskStorage;
+use crate::models::task::Task;
+use chrono::{DateTime, Local, Utc};
+use colored::Colorize;
+use serde_json::json;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// "2024-05-01 14:03 (3 days ago)"
+fn format_time(time: DateTime<Utc>) -> String {
+    let age = UNIX_EPOCH + Duration::from_secs(time.timestamp().max(0) as u64);
+    format!(
+        "{} ({})",
+        time.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+        format_age(age)
+    )
+}
+
+/// Whether the task branch was pushed, but its remote branch was deleted since
+fn upstream_gone(task: &Task, info: &WorktreeInfo) -> bool {
+    match (task.main_repo(), &info.branch) {
+        (Some(main_repo), Some(branch)) => {
+            git::upstream_gone(&main_repo, branch, task.work_start())
+        }
+        _ => false,
+    }
+}
+
+pub fn execute(selector: TaskSelector, json: bool) -> TmResult<()> {
+    let tasks_file = get_tasks_file_path()?;
+    let storage = TaskStorage::load(&tasks_file)?;
+    let (project, title) = storage.resolve(&selector)?;
+    let task = storage.get_task(&project, &title)?;
+
+    let health = Health::of(task);
+
+    if json {
+        print_json(&project, task, &health)
+    } else {
+        print_human(&project, task, &health);
+        Ok(())
+    }
+}
+
+fn print_human(project: &str, task: &Task, health: &Health) {
+    let field = |name: &str, value: &str| println!("  {:<15}{}", format!("{}:", name), value);
+
+    println!("{}/{}", project, task.title.bold());
+    field("status", task.status.as_str());
+    for (name, value) in [
+        ("reference", &task.reference),
+        ("description", &task.description),
+        ("remote url", &task.remote_url),
+        ("api url", &task.api_url),
+        ("base", &task.base_branch),
+    ] {
+        if let Some(value) = value {
+            field(name, value);
+        }
+    }
+    for (name, time) in [
+        ("created", task.created_at),
+        ("updated", task.updated_at),
+        ("last switch", task.last_switched_at),
+    ] {
+        if let Some(time) = time {
+            field(name, &format_time(time));
+        }
+    }
+
+    field("worktree", &task.worktree_path.display().to_string());
+    let info = match health {
+        Health::Ok(info) => info,
+        Health::Missing => {
+            field("state", &"missing".red().to_string());
+            return;
+        }
+        Health::Error(e) => {
+            field("state", &format!("{} {}", "error:".red(), e));
+            return;
+        }
+    };
+
+    field("branch", health.branch());
+    match &info.upstream {
+        Some(upstream) => field("upstream", upstream),
+        None if upstream_gone(task, info) => {
+            field("upstream", &"gone from the remote".yellow().to_string())
+        }
+        None => field("upstream", "-"),
+    }
+    if let Some(base) = &info.base {
+        field(
+            "ahead/behind",
+            &format!("↑{} ↓{} relative to {}", info.ahead, info.behind, base),
+        );
+    }
+    if let Some(head) = &info.head {
+        field(
+            "HEAD",
+            &format!("{} {}", short_commit(&head.id), head.summary),
+        );
+        if let Some(time) = DateTime::from_timestamp(head.time, 0) {
+            field("committed", &format_time(time));
+        }
+    }
+
+    if info.changes.is_empty() {
+        field("changes", &"clean".green().to_string());
+    } else {
+        field(
+            "changes",
+            &format!("{} file(s)", info.changes.len())
+                .yellow()
+                .to_string(),
+        );
+        for change in &info.changes {
+            println!("    {} {}", change.status, change.path);
+        }
+    }
+}
+
+fn print_json(project: &str, task: &Task, health: &Health) -> TmResult<()> {
+    let mut value = json!({
+        "project": project,
+        "title": task.title,
+        "status": task.status,
+        "reference": task.reference,
+        "description": task.description,
+        "remote_url": task.remote_url,
+        "api_url": task.api_url,
+        "base_branch": task.base_branch,
+        "worktree_path": task.worktree_path,
+        "main_repo_path": task.main_repo_path,
+        "created_at": task.created_at,
+        "updated_at": task.updated_at,
+        "last_switched_at": task.last_switched_at,
+    });
+
+    value["worktree"] = match health {
+        Health::Ok(info) => json!({
+            "missing": false,
+            "branch": info.branch,
+            "upstream": info.upstream,
+            "upstream_gone": info.upstream.is_none() && upstream_gone(task, info),
+            "base": info.base,
+            "ahead": info.ahead,
+            "behind": info.behind,
+            "head": info.head.as_ref().map(|head| json!({
+                "id": head.id,
+                "summary": head.summary,
+                "time": DateTime::from_timestamp(head.time, 0),
+            })),
+            "dirty": !info.changes.is_empty(),
+            "changes": info
+                .changes
+                .iter()
+                .map(|change| json!({ "status": change.status, "path": change.path }))
+                .collect::<Vec<_>>(),
+        }),
+        Health::Missing => json!({ "missing": true }),
+        Health::Error(e) => json!({ "missing": false, "error": e }),
+    };
+
+    println!("{}", serde_json::to_string_pretty(&value)?);
+
+    Ok(())
+}
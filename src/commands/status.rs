@@ -0,0 +1,57 @@
+use crate::config::get_tasks_file_path;
+use crate::error::{TmError, TmResult};
+use crate::models::history::{self, EventKind};
+use crate::models::{selector::TaskSelector, storage::TaskStorage, task::TaskStatus};
+use clap::ValueEnum;
+
+/// Split `STATE`, `TASK STATE` or `PROJECT TITLE STATE` arguments
+fn parse_args(mut args: Vec<String>) -> TmResult<(TaskSelector, TaskStatus)> {
+    let state = args.pop().unwrap_or_default();
+    let state = TaskStatus::from_str(&state, true).map_err(|_| TmError::InvalidInput {
+        field: "status".to_string(),
+        reason: format!("'{}' is not one of todo, in-progress, review, done", state),
+    })?;
+
+    let mut args = args.into_iter();
+    Ok((TaskSelector::new(args.next(), args.next()), state))
+}
+
+pub fn execute(args: Vec<String>, force: bool) -> TmResult<()> {
+    let (selector, state) = parse_args(args)?;
+
+    let tasks_file = get_tasks_file_path()?;
+    let (mut storage, _lock) = TaskStorage::load_locked(&tasks_file)?;
+    let (project, title) = storage.resolve(&selector)?;
+
+    let task = storage.get_task_mut(&project, &title)?;
+
+    if task.status == state {
+        println!("Task '{}' is already {}", title, state);
+        return Ok(());
+    }
+
+    if !force && !task.status.can_transition_to(state) {
+        return Err(TmError::InvalidStatusTransition {
+            title,
+            from: task.status,
+            to: state,
+        });
+    }
+
+    let previous = task.status;
+    task.status = state;
+    task.touch();
+    storage.save(&tasks_file)?;
+    history::record(
+        &project,
+        &title,
+        EventKind::StatusChanged {
+            from: previous,
+            to: state,
+        },
+    );
+
+    println!("Task '{}': {} -> {}", title, previous, state);
+
+    Ok(())
+}
@@ -1,19 +1,96 @@
 use crate::config::get_tasks_file_path;
-use crate::error::TmResult;
-use crate::git::validate_worktree;
-use crate::models::storage::TaskStorage;
+use crate::error::{TmError, TmResult};
+use crate::git::{self, validate_worktree};
+use crate::models::history::{self, EventKind};
+use crate::models::{selector::TaskSelector, storage::TaskStorage};
+use crate::picker;
+use chrono::Utc;
+use std::io::IsTerminal;
 
-pub fn execute(project: String, title: String) -> TmResult<()> {
+/// Print a task's worktree path for shell integration and remember the switch
+fn switch_to(project: &str, title: &str) -> TmResult<()> {
     let tasks_file = get_tasks_file_path()?;
-    let storage = TaskStorage::load(&tasks_file)?;
-
-    let task = storage.get_task(&project, &title)?;
+    let (mut storage, _lock) = TaskStorage::load_locked(&tasks_file)?;
+    let task = storage.get_task_mut(project, title)?;
 
     // Validate worktree still exists
     validate_worktree(&task.worktree_path)?;
 
+    task.last_switched_at = Some(Utc::now());
+    let worktree_path = task.worktree_path.clone();
+    storage.save_without_backup(&tasks_file)?;
+    history::record(project, title, EventKind::Switched);
+
     // Output only the path for shell integration
-    println!("{}", task.worktree_path.display());
+    println!("{}", worktree_path.display());
 
     Ok(())
 }
+
+pub fn execute(selector: TaskSelector) -> TmResult<()> {
+    let tasks_file = get_tasks_file_path()?;
+    let storage = TaskStorage::load(&tasks_file)?;
+
+    let (project, title) = storage.resolve(&selector)?;
+
+    switch_to(&project, &title)
+}
+
+/// Choose the task in an interactive fuzzy finder
+pub fn pick() -> TmResult<()> {
+    // The picker draws on stderr and reads keys from the terminal
+    if !std::io::stderr().is_terminal() {
+        return Err(TmError::InvalidInput {
+            field: "task".to_string(),
+            reason: "no task given and no terminal to pick one in".to_string(),
+        });
+    }
+
+    let tasks_file = get_tasks_file_path()?;
+    let storage = TaskStorage::load(&tasks_file)?;
+
+    let mut tasks = storage.list_tasks(None);
+    if tasks.is_empty() {
+        println!("No tasks found.");
+        return Ok(());
+    }
+    tasks.sort_by(|a, b| (a.0, &a.1.title).cmp(&(b.0, &b.1.title)));
+
+    // Step 1: Build one aligned line per task
+    let project_w = tasks.iter().map(|(p, _)| p.len()).max().unwrap_or(0) + 2;
+    let title_w = tasks.iter().map(|(_, t)| t.title.len()).max().unwrap_or(0) + 2;
+    let reference_w = tasks
+        .iter()
+        .map(|(_, t)| t.reference.as_deref().map_or(1, str::len))
+        .max()
+        .unwrap_or(0)
+        + 2;
+
+    let items: Vec<String> = tasks
+        .iter()
+        .map(|(project, task)| {
+            let state =
+                match git::get_worktree_info(&task.worktree_path, task.base_branch.as_deref()) {
+                    Ok(info) if info.has_uncommitted_changes => "dirty",
+                    Ok(_) => "clean",
+                    Err(_) => "missing",
+                };
+            format!(
+                "{:<project_w$}{:<title_w$}{:<reference_w$}{:<9}{}",
+                project,
+                task.title,
+                task.reference.as_deref().unwrap_or("-"),
+                state,
+                task.description.as_deref().unwrap_or(""),
+            )
+            .trim_end()
+            .to_string()
+        })
+        .collect();
+
+    // Step 2: Let the user choose and print the path like `tm switch <task>`
+    match picker::pick("task", &items)? {
+        Some(index) => switch_to(tasks[index].0, &tasks[index].1.title),
+        None => Err(TmError::Cancelled),
+    }
+}
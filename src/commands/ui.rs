@@ -0,0 +1,744 @@
+use crate::commands::list::Health;
+use crate::commands::{add, archive, remove, switch};
+use crate::config::{get_tasks_file_path, Config};
+use crate::error::{TmError, TmResult};
+use crate::git;
+use crate::level::Levels;
+use crate::models::selector::TaskSelector;
+use crate::models::storage::TaskStorage;
+use crate::models::task::Task;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{
+    Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Table, TableState, Wrap,
+};
+use ratatui::{Frame, Terminal};
+use std::collections::{BTreeSet, HashMap};
+use std::io::{stderr, IsTerminal, Stderr, Write};
+use std::os::fd::RawFd;
+use std::path::PathBuf;
+
+/// Number of commits shown in the detail pane
+const RECENT_COMMITS: usize = 5;
+
+/// Points stdout at stderr while alive, so the output of commands run from the dashboard
+/// reaches the terminal even when stdout is captured, e.g. by `cd "$(tm ui)"`
+struct StdoutToStderr {
+    saved: RawFd,
+}
+
+impl StdoutToStderr {
+    fn new() -> std::io::Result<Self> {
+        std::io::stdout().flush()?;
+
+        // SAFETY: only duplicates the process's own standard descriptors
+        let saved = unsafe { libc::dup(libc::STDOUT_FILENO) };
+        if saved < 0 || unsafe { libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) } < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        Ok(Self { saved })
+    }
+}
+
+impl Drop for StdoutToStderr {
+    fn drop(&mut self) {
+        let _ = std::io::stdout().flush();
+
+        // SAFETY: `saved` is a descriptor this guard owns
+        unsafe {
+            libc::dup2(self.saved, libc::STDOUT_FILENO);
+            libc::close(self.saved);
+        }
+    }
+}
+
+/// The dashboard's terminal, drawn on stderr like the picker so stdout stays free
+/// for the path printed on switch; restored on drop, also when an error occurs
+struct Screen {
+    terminal: Terminal<CrosstermBackend<Stderr>>,
+}
+
+impl Screen {
+    fn enter() -> TmResult<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(stderr(), EnterAlternateScreen)?;
+        Ok(Self {
+            terminal: Terminal::new(CrosstermBackend::new(stderr()))?,
+        })
+    }
+
+    /// Leave the dashboard to run a command, showing its output until a key is pressed
+    fn suspend(&mut self, run: impl FnOnce() -> TmResult<()>) -> TmResult<()> {
+        execute!(stderr(), LeaveAlternateScreen)?;
+        terminal::disable_raw_mode()?;
+
+        {
+            let _redirect = StdoutToStderr::new()?;
+            if let Err(e) = run() {
+                eprintln!("Error: {}", e.user_message());
+            }
+        }
+
+        eprintln!();
+        eprintln!("Press any key to return to tm ui");
+        terminal::enable_raw_mode()?;
+        loop {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    break;
+                }
+            }
+        }
+
+        execute!(stderr(), EnterAlternateScreen)?;
+        self.terminal.clear()?;
+        Ok(())
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = self.terminal.show_cursor();
+        let _ = execute!(stderr(), LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// A task with the live state of its worktree
+struct TaskRow {
+    project: String,
+    task: Task,
+    health: Health,
+}
+
+#[derive(PartialEq, Eq)]
+enum Focus {
+    Projects,
+    Tasks,
+}
+
+/// Fields of the new task form, in order
+const FORM_FIELDS: [&str; 5] = ["Project", "Level", "ID", "Name", "Description"];
+
+struct Form {
+    values: [String; 5],
+    active: usize,
+}
+
+enum Mode {
+    Normal,
+    Create(Form),
+    ConfirmRemove,
+}
+
+/// What the dashboard asks `execute` to do after a key press
+enum Action {
+    Quit,
+    Switch {
+        project: String,
+        title: String,
+    },
+    Create {
+        project: String,
+        level: Option<crate::level::Level>,
+        id: String,
+        name: String,
+        description: Option<String>,
+    },
+    Remove {
+        project: String,
+        title: String,
+        worktree: bool,
+    },
+    Archive {
+        project: String,
+        title: String,
+    },
+}
+
+struct App {
+    /// Project names; the sidebar shows "All" before them
+    projects: Vec<String>,
+    project_state: ListState,
+    rows: Vec<TaskRow>,
+    task_state: TableState,
+    focus: Focus,
+    mode: Mode,
+    levels: Levels,
+    commits: HashMap<PathBuf, Vec<String>>,
+    message: Option<String>,
+}
+
+impl App {
+    fn load() -> TmResult<Self> {
+        let mut app = Self {
+            projects: Vec::new(),
+            project_state: ListState::default().with_selected(Some(0)),
+            rows: Vec::new(),
+            task_state: TableState::default(),
+            focus: Focus::Tasks,
+            mode: Mode::Normal,
+            levels: Levels::builtin(),
+            commits: HashMap::new(),
+            message: None,
+        };
+        app.reload()?;
+        Ok(app)
+    }
+
+    /// Re-read tasks, configuration and worktree state
+    fn reload(&mut self) -> TmResult<()> {
+        let config = Config::load()?;
+        let storage = TaskStorage::load(&get_tasks_file_path()?)?;
+
+        let mut projects: BTreeSet<String> = config.projects.keys().cloned().collect();
+        let mut rows: Vec<TaskRow> = storage
+            .list_tasks(None)
+            .into_iter()
+            .map(|(project, task)| {
+                projects.insert(project.to_string());
+                TaskRow {
+                    project: project.to_string(),
+                    task: task.clone(),
+                    health: Health::of(task),
+                }
+            })
+            .collect();
+        rows.sort_by(|a, b| (&a.project, &a.task.title).cmp(&(&b.project, &b.task.title)));
+
+        self.levels = config.levels()?;
+        self.projects = projects.into_iter().collect();
+        self.rows = rows;
+        self.commits.clear();
+
+        let project = self.project_state.selected().unwrap_or(0);
+        self.project_state
+            .select(Some(project.min(self.projects.len())));
+        self.clamp_task();
+        Ok(())
+    }
+
+    /// Selected project, None for "All"
+    fn selected_project(&self) -> Option<&str> {
+        match self.project_state.selected() {
+            Some(i) if i > 0 => self.projects.get(i - 1).map(String::as_str),
+            _ => None,
+        }
+    }
+
+    fn visible(&self) -> Vec<&TaskRow> {
+        let project = self.selected_project();
+        self.rows
+            .iter()
+            .filter(|row| project.is_none_or(|p| row.project == p))
+            .collect()
+    }
+
+    fn selected_row(&self) -> Option<&TaskRow> {
+        self.task_state
+            .selected()
+            .and_then(|i| self.visible().get(i).copied())
+    }
+
+    fn clamp_task(&mut self) {
+        let count = self.visible().len();
+        let selected = match self.task_state.selected() {
+            _ if count == 0 => None,
+            Some(i) => Some(i.min(count - 1)),
+            None => Some(0),
+        };
+        self.task_state.select(selected);
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        match self.focus {
+            Focus::Projects => {
+                let last = self.projects.len();
+                let i = self.project_state.selected().unwrap_or(0);
+                let i = if down {
+                    (i + 1).min(last)
+                } else {
+                    i.saturating_sub(1)
+                };
+                self.project_state.select(Some(i));
+                self.task_state.select(Some(0));
+                self.clamp_task();
+            }
+            Focus::Tasks => {
+                let last = self.visible().len().saturating_sub(1);
+                let i = self.task_state.selected().unwrap_or(0);
+                let i = if down {
+                    (i + 1).min(last)
+                } else {
+                    i.saturating_sub(1)
+                };
+                self.task_state.select(Some(i));
+            }
+        }
+    }
+
+    /// Load the recent commits of the selected task, once per worktree
+    fn load_commits(&mut self) {
+        let Some(path) = self
+            .selected_row()
+            .map(|row| row.task.worktree_path.clone())
+        else {
+            return;
+        };
+        self.commits
+            .entry(path.clone())
+            .or_insert_with(|| git::recent_commits(&path, RECENT_COMMITS).unwrap_or_default());
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Some(Action::Quit);
+        }
+
+        match std::mem::replace(&mut self.mode, Mode::Normal) {
+            Mode::Normal => self.handle_normal_key(key),
+            Mode::Create(form) => self.handle_form_key(form, key),
+            Mode::ConfirmRemove => {
+                let row = self.selected_row()?;
+                let worktree = match key.code {
+                    KeyCode::Char('y') => false,
+                    KeyCode::Char('w') => true,
+                    _ => return None,
+                };
+                Some(Action::Remove {
+                    project: row.project.clone(),
+                    title: row.task.title.clone(),
+                    worktree,
+                })
+            }
+        }
+    }
+
+    fn handle_normal_key(&mut self, key: KeyEvent) -> Option<Action> {
+        self.message = None;
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Action::Quit),
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Focus::Projects => Focus::Tasks,
+                    Focus::Tasks => Focus::Projects,
+                }
+            }
+            KeyCode::Left | KeyCode::Char('h') => self.focus = Focus::Projects,
+            KeyCode::Right | KeyCode::Char('l') => self.focus = Focus::Tasks,
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(false),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(true),
+            KeyCode::Enter if self.focus == Focus::Projects => self.focus = Focus::Tasks,
+            KeyCode::Enter => {
+                let row = self.selected_row()?;
+                return Some(Action::Switch {
+                    project: row.project.clone(),
+                    title: row.task.title.clone(),
+                });
+            }
+            KeyCode::Char('a') => {
+                let mut values: [String; 5] = Default::default();
+                values[0] = self.selected_project().unwrap_or_default().to_string();
+                let active = if values[0].is_empty() { 0 } else { 1 };
+                self.mode = Mode::Create(Form { values, active });
+            }
+            KeyCode::Char('x') => {
+                let row = self.selected_row()?;
+                return Some(Action::Archive {
+                    project: row.project.clone(),
+                    title: row.task.title.clone(),
+                });
+            }
+            KeyCode::Char('d') if self.selected_row().is_some() => self.mode = Mode::ConfirmRemove,
+            KeyCode::Char('r') => {
+                if let Err(e) = self.reload() {
+                    self.message = Some(e.user_message());
+                }
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    fn handle_form_key(&mut self, mut form: Form, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Esc => return None,
+            KeyCode::Tab | KeyCode::Down => form.active = (form.active + 1) % FORM_FIELDS.len(),
+            KeyCode::BackTab | KeyCode::Up => {
+                form.active = (form.active + FORM_FIELDS.len() - 1) % FORM_FIELDS.len()
+            }
+            KeyCode::Backspace => {
+                form.values[form.active].pop();
+            }
+            KeyCode::Char(c) => form.values[form.active].push(c),
+            KeyCode::Enter => {
+                let [project, level, id, name, description] = form.values.clone();
+                let level = match level.trim() {
+                    "" => None,
+                    level => match self.levels.find(level) {
+                        Some(level) => Some(level.clone()),
+                        None => {
+                            self.message = Some(format!(
+                                "Unknown level '{}' (known: {})",
+                                level,
+                                self.levels.names().join(", ")
+                            ));
+                            self.mode = Mode::Create(form);
+                            return None;
+                        }
+                    },
+                };
+                if project.trim().is_empty() || id.trim().is_empty() || name.trim().is_empty() {
+                    self.message = Some("Project, ID and name are required".to_string());
+                    self.mode = Mode::Create(form);
+                    return None;
+                }
+
+                return Some(Action::Create {
+                    project: project.trim().to_string(),
+                    level,
+                    id: id.trim().to_string(),
+                    name: name.trim().to_string(),
+                    description: Some(description.trim().to_string()).filter(|d| !d.is_empty()),
+                });
+            }
+            _ => {}
+        }
+
+        self.mode = Mode::Create(form);
+        None
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, footer] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let sidebar_width = self
+            .projects
+            .iter()
+            .map(|p| p.len())
+            .max()
+            .unwrap_or(0)
+            .max(12) as u16
+            + 8;
+        let [sidebar, right] =
+            Layout::horizontal([Constraint::Length(sidebar_width), Constraint::Min(0)]).areas(main);
+        let [tasks_area, detail_area] =
+            Layout::vertical([Constraint::Percentage(55), Constraint::Min(0)]).areas(right);
+
+        self.draw_projects(frame, sidebar);
+        self.draw_tasks(frame, tasks_area);
+        self.draw_detail(frame, detail_area);
+
+        let help = match self.mode {
+            Mode::Normal => {
+                "enter switch  a add  x archive  d remove  r refresh  tab/h/l focus  j/k move  q quit"
+            }
+            Mode::Create(_) => "tab next field  enter create  esc cancel",
+            Mode::ConfirmRemove => "y remove task  w also remove worktree  esc cancel",
+        };
+        let footer_line = match self.message {
+            Some(ref message) => Line::styled(message.as_str(), Style::new().fg(Color::Red)),
+            None => Line::styled(help, Style::new().add_modifier(Modifier::DIM)),
+        };
+        frame.render_widget(Paragraph::new(footer_line), footer);
+
+        match self.mode {
+            Mode::Create(ref form) => draw_form(frame, form),
+            Mode::ConfirmRemove => {
+                if let Some(row) = self.selected_row() {
+                    let text = format!(
+                        "Remove '{}/{}'?\n\ny: remove the task\nw: also remove the worktree\nesc: cancel",
+                        row.project, row.task.title
+                    );
+                    draw_popup(frame, "Remove", Paragraph::new(text), 6);
+                }
+            }
+            Mode::Normal => {}
+        }
+    }
+
+    fn block(&self, title: &str, focus: Focus) -> Block<'static> {
+        let style = if self.focus == focus && matches!(self.mode, Mode::Normal) {
+            Style::new().fg(Color::Cyan)
+        } else {
+            Style::new()
+        };
+        Block::new()
+            .borders(Borders::ALL)
+            .border_style(style)
+            .title(format!(" {} ", title))
+    }
+
+    fn draw_projects(&mut self, frame: &mut Frame, area: Rect) {
+        let count = |project: Option<&str>| {
+            self.rows
+                .iter()
+                .filter(|row| project.is_none_or(|p| row.project == p))
+                .count()
+        };
+
+        let mut items = vec![ListItem::new(format!("All ({})", count(None)))];
+        items.extend(
+            self.projects
+                .iter()
+                .map(|p| ListItem::new(format!("{} ({})", p, count(Some(p))))),
+        );
+
+        let list = List::new(items)
+            .block(self.block("Projects", Focus::Projects))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.project_state);
+    }
+
+    fn draw_tasks(&mut self, frame: &mut Frame, area: Rect) {
+        let show_project = self.selected_project().is_none();
+        let rows: Vec<Row> = self
+            .visible()
+            .iter()
+            .map(|row| {
+                let summary_style = match row.health {
+                    Health::Ok(ref info) if !info.has_uncommitted_changes => {
+                        Style::new().fg(Color::Green)
+                    }
+                    Health::Ok(_) => Style::new().fg(Color::Yellow),
+                    _ => Style::new().fg(Color::Red),
+                };
+                let title = if show_project {
+                    format!("{}/{}", row.project, row.task.title)
+                } else {
+                    row.task.title.clone()
+                };
+                Row::new(vec![
+                    Cell::from(title),
+                    Cell::from(row.task.status.as_str()),
+                    Cell::from(row.health.branch().to_string()),
+                    Cell::from(Span::styled(row.health.summary(), summary_style)),
+                ])
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(3),
+                Constraint::Length(13),
+                Constraint::Fill(2),
+                Constraint::Length(16),
+            ],
+        )
+        .header(
+            Row::new(["TITLE", "STATUS", "BRANCH", "WORKTREE"])
+                .style(Style::new().add_modifier(Modifier::BOLD)),
+        )
+        .block(self.block("Tasks", Focus::Tasks))
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, area, &mut self.task_state);
+    }
+
+    fn draw_detail(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::new().borders(Borders::ALL).title(" Details ");
+        let Some(row) = self.selected_row() else {
+            frame.render_widget(Paragraph::new("No task selected").block(block), area);
+            return;
+        };
+        let task = &row.task;
+
+        let field = |name: &str, value: String| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{:<13}", name),
+                    Style::new().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(value),
+            ])
+        };
+
+        let mut lines = vec![
+            field("Project", row.project.clone()),
+            field("Title", task.title.clone()),
+            field(
+                "Reference",
+                task.reference.clone().unwrap_or_else(|| "-".to_string()),
+            ),
+            field("Status", task.status.to_string()),
+            field("Worktree", task.worktree_path.display().to_string()),
+        ];
+        if let Some(ref base) = task.base_branch {
+            lines.push(field("Base", base.clone()));
+        }
+        if let Some(ref description) = task.description {
+            lines.push(Line::raw(""));
+            lines.push(Line::raw(description.clone()));
+        }
+
+        if let Some(commits) = self.commits.get(&task.worktree_path) {
+            if !commits.is_empty() {
+                lines.push(Line::raw(""));
+                lines.push(Line::styled(
+                    "Recent commits",
+                    Style::new().add_modifier(Modifier::BOLD),
+                ));
+                lines.extend(commits.iter().map(|c| Line::raw(format!("  {}", c))));
+            }
+        }
+
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(block)
+                .wrap(Wrap { trim: false }),
+            area,
+        );
+    }
+}
+
+/// A bordered box in the middle of the screen
+fn draw_popup(frame: &mut Frame, title: &str, content: Paragraph, height: u16) {
+    let area = frame.area();
+    let width = area.width.min(64);
+    let height = (height + 2).min(area.height);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        content.block(
+            Block::new()
+                .borders(Borders::ALL)
+                .border_style(Style::new().fg(Color::Cyan))
+                .title(format!(" {} ", title)),
+        ),
+        popup,
+    );
+}
+
+fn draw_form(frame: &mut Frame, form: &Form) {
+    let lines: Vec<Line> = FORM_FIELDS
+        .iter()
+        .zip(&form.values)
+        .enumerate()
+        .map(|(i, (name, value))| {
+            let style = if i == form.active {
+                Style::new().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::new()
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{:<13}", name),
+                    Style::new().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(format!("{} ", value), style),
+            ])
+        })
+        .collect();
+
+    draw_popup(
+        frame,
+        "New task",
+        Paragraph::new(lines),
+        FORM_FIELDS.len() as u16,
+    );
+}
+
+pub fn execute() -> TmResult<()> {
+    if !stderr().is_terminal() {
+        return Err(TmError::InvalidInput {
+            field: "terminal".to_string(),
+            reason: "tm ui needs a terminal".to_string(),
+        });
+    }
+
+    let mut app = App::load()?;
+    let mut screen = Screen::enter()?;
+
+    loop {
+        app.load_commits();
+        screen.terminal.draw(|frame| app.draw(frame))?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match app.handle_key(key) {
+            None => {}
+            Some(Action::Quit) => return Ok(()),
+            Some(Action::Switch { project, title }) => {
+                // Restore the terminal before printing the path like `tm switch`
+                drop(screen);
+                return switch::execute(TaskSelector::InProject {
+                    project,
+                    query: title,
+                });
+            }
+            Some(Action::Create {
+                project,
+                level,
+                id,
+                name,
+                description,
+            }) => {
+                screen.suspend(|| {
+                    add::execute(
+                        project,
+                        None,
+                        level,
+                        id,
+                        name,
+                        description,
+                        None,
+                        None,
+                        false,
+                        None,
+                        None,
+                    )
+                })?;
+                app.reload()?;
+            }
+            Some(Action::Remove {
+                project,
+                title,
+                worktree,
+            }) => {
+                screen.suspend(|| {
+                    remove::execute(
+                        TaskSelector::InProject {
+                            project,
+                            query: title,
+                        },
+                        worktree,
+                        false,
+                    )
+                })?;
+                app.reload()?;
+            }
+            Some(Action::Archive { project, title }) => {
+                screen.suspend(|| {
+                    archive::execute(
+                        TaskSelector::InProject {
+                            project,
+                            query: title,
+                        },
+                        false,
+                        false,
+                    )
+                })?;
+                app.reload()?;
+            }
+        }
+    }
+}
@@ -1,5 +1,14 @@
-use crate::error::TmResult;
-use std::path::PathBuf;
+use crate::error::{TmError, TmResult};
+use crate::git;
+use crate::level::{Level, Levels};
+use crate::models::storage::write_atomic;
+use crate::template::{Template, DEFAULT_BRANCH_TEMPLATE, DEFAULT_WORKTREE_TEMPLATE};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Name of the optional per-repository config file in the main repository
+pub const REPO_CONFIG_FILE: &str = ".tm.toml";
 
 /// Get the path to the tasks.toml file
 pub fn get_tasks_file_path() -> TmResult<PathBuf> {
@@ -13,8 +22,17 @@ pub fn get_tasks_file_path() -> TmResult<PathBuf> {
     Ok(config_dir.join("tm").join("tasks.toml"))
 }
 
+/// Get the path to the config.toml file
+pub fn get_config_file_path() -> TmResult<PathBuf> {
+    Ok(get_tasks_file_path()?.with_file_name("config.toml"))
+}
+
+/// Get the path to the history.jsonl file of task events
+pub fn get_history_file_path() -> TmResult<PathBuf> {
+    Ok(get_tasks_file_path()?.with_file_name("history.jsonl"))
+}
+
 /// Ensure the config directory exists
-#[allow(dead_code)]
 pub fn ensure_config_dir() -> TmResult<PathBuf> {
     let config_dir = dirs::config_dir()
         .ok_or_else(|| {
@@ -29,3 +47,307 @@ pub fn ensure_config_dir() -> TmResult<PathBuf> {
 
     Ok(config_dir)
 }
+
+/// Expand a leading `~` to the home directory
+pub fn expand_tilde(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Lexically resolve `.` and `..` components, e.g. "/a/main/../wt" -> "/a/wt"
+fn normalize(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if result.file_name().is_some() {
+                    result.pop();
+                } else if !result.has_root() {
+                    result.push("..");
+                }
+            }
+            other => result.push(other),
+        }
+    }
+    result
+}
+
+/// Shell commands run at points in a task's lifecycle
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Hooks {
+    /// Run in the new worktree after it is created
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_create: Vec<String>,
+
+    /// Run in the worktree before it is removed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_remove: Vec<String>,
+}
+
+impl Hooks {
+    pub fn is_empty(&self) -> bool {
+        self.post_create.is_empty() && self.pre_remove.is_empty()
+    }
+}
+
+/// A task level defined or overridden in `[levels.<name>]` of config.toml
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LevelConfig {
+    /// First component of branch names, defaults to the level name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+
+    /// Directory name for worktrees, defaults to the level name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+
+    /// Color of task titles in `tm list`, e.g. "red" or "bright blue"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+
+    /// Other names accepted for the level, e.g. "hf" for "hotfix"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+}
+
+/// Defaults for a project, from `[projects.<name>]` in config.toml or from .tm.toml
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProjectConfig {
+    /// Path to the main repository (ignored in .tm.toml)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub main_repo: Option<PathBuf>,
+
+    /// Default base for new task branches
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_branch: Option<String>,
+
+    /// Default task level
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+
+    /// Directory worktrees are created in, defaults to the parent of the main repository
+    /// Relative paths in .tm.toml are resolved against the main repository
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worktree_root: Option<PathBuf>,
+
+    /// Template for new branch names, e.g. "{user}/{level}/{id}-{name:kebab}"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch_template: Option<String>,
+
+    /// Template for worktree paths relative to the worktree root, e.g. "{id}"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worktree_template: Option<String>,
+
+    /// Lifecycle hooks (ignored in .tm.toml so cloning a repository never runs its commands)
+    #[serde(default, skip_serializing_if = "Hooks::is_empty")]
+    pub hooks: Hooks,
+}
+
+impl ProjectConfig {
+    /// Check values that can't be expressed in the TOML types
+    fn validate(&self, source: &Path, levels: &Levels) -> TmResult<()> {
+        let invalid = |reason: String| TmError::InvalidConfig {
+            path: source.to_path_buf(),
+            reason,
+        };
+
+        if let Some(ref level) = self.level {
+            if levels.find(level).is_none() {
+                return Err(invalid(format!("unknown level '{}'", level)));
+            }
+        }
+        if let Some(ref template) = self.branch_template {
+            Template::parse_branch(template)
+                .map_err(|e| invalid(format!("branch_template: {}", e)))?;
+        }
+        if let Some(ref template) = self.worktree_template {
+            Template::parse_worktree(template)
+                .map_err(|e| invalid(format!("worktree_template: {}", e)))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// User configuration stored in ~/.config/tm/config.toml
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Config {
+    /// Value for {user} in templates, defaults to $USER
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+
+    /// Custom task levels and overrides of the built-in ones, keyed by level name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub levels: BTreeMap<String, LevelConfig>,
+
+    /// Per-project defaults keyed by project name
+    #[serde(default)]
+    pub projects: BTreeMap<String, ProjectConfig>,
+}
+
+impl Config {
+    /// Load the user configuration, empty if it doesn't exist
+    pub fn load() -> TmResult<Self> {
+        let path = get_config_file_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(&path)?;
+        let config: Config = toml::from_str(&content).map_err(|e| TmError::InvalidConfig {
+            path: path.clone(),
+            reason: e.to_string(),
+        })?;
+
+        let levels =
+            Levels::from_config(&config.levels).map_err(|reason| TmError::InvalidConfig {
+                path: path.clone(),
+                reason,
+            })?;
+        for project in config.projects.values() {
+            project.validate(&path, &levels)?;
+        }
+
+        Ok(config)
+    }
+
+    /// Save the user configuration
+    pub fn save(&self) -> TmResult<()> {
+        ensure_config_dir()?;
+        let path = get_config_file_path()?;
+        write_atomic(&path, &toml::to_string_pretty(self)?)
+    }
+
+    /// The built-in levels merged with the configured ones
+    pub fn levels(&self) -> TmResult<Levels> {
+        Levels::from_config(&self.levels).map_err(|reason| TmError::InvalidConfig {
+            path: get_config_file_path().unwrap_or_default(),
+            reason,
+        })
+    }
+
+    /// Resolve the settings for a project
+    /// Precedence: command line, config.toml, the repository's .tm.toml, built-in defaults
+    pub fn settings(&self, project: &str, main_repo: Option<PathBuf>) -> TmResult<ProjectSettings> {
+        let user = self.projects.get(project).cloned().unwrap_or_default();
+
+        let main_repo = main_repo
+            .or_else(|| user.main_repo.as_deref().map(expand_tilde))
+            .ok_or_else(|| TmError::MissingMainRepo {
+                project: project.to_string(),
+            })?;
+
+        let levels = self.levels()?;
+        let repo = load_repo_config(&main_repo, &levels)?.unwrap_or_default();
+
+        let level = match user.level.or(repo.level) {
+            Some(level) => levels.find(&level).cloned(),
+            None => None,
+        };
+
+        let worktree_root = match (user.worktree_root, repo.worktree_root) {
+            (Some(root), _) => expand_tilde(&root),
+            (None, Some(root)) => normalize(&main_repo.join(expand_tilde(&root))),
+            (None, None) => git::get_repo_root(&main_repo)?,
+        };
+
+        let branch_template = user
+            .branch_template
+            .or(repo.branch_template)
+            .unwrap_or_else(|| DEFAULT_BRANCH_TEMPLATE.to_string());
+        let branch_template =
+            Template::parse_branch(&branch_template).map_err(|reason| TmError::InvalidInput {
+                field: "branch_template".to_string(),
+                reason,
+            })?;
+
+        let worktree_template = user
+            .worktree_template
+            .or(repo.worktree_template)
+            .unwrap_or_else(|| DEFAULT_WORKTREE_TEMPLATE.to_string());
+        let worktree_template = Template::parse_worktree(&worktree_template).map_err(|reason| {
+            TmError::InvalidInput {
+                field: "worktree_template".to_string(),
+                reason,
+            }
+        })?;
+
+        Ok(ProjectSettings {
+            user: self
+                .user
+                .clone()
+                .or_else(|| std::env::var("USER").ok())
+                .unwrap_or_else(|| "user".to_string()),
+            base_branch: user.base_branch.or(repo.base_branch),
+            level,
+            worktree_root,
+            branch_template,
+            worktree_template,
+            hooks: user.hooks,
+            main_repo,
+        })
+    }
+}
+
+/// Load .tm.toml from a main repository, if there is one
+fn load_repo_config(main_repo: &Path, levels: &Levels) -> TmResult<Option<ProjectConfig>> {
+    let path = main_repo.join(REPO_CONFIG_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = std::fs::read_to_string(&path)?;
+    let config: ProjectConfig = toml::from_str(&content).map_err(|e| TmError::InvalidConfig {
+        path: path.clone(),
+        reason: e.to_string(),
+    })?;
+    config.validate(&path, levels)?;
+
+    Ok(Some(config))
+}
+
+/// Effective settings for a project after merging all config sources
+#[derive(Debug)]
+pub struct ProjectSettings {
+    pub main_repo: PathBuf,
+    /// Value for {user} in templates
+    pub user: String,
+    pub base_branch: Option<String>,
+    pub level: Option<Level>,
+    pub worktree_root: PathBuf,
+    pub branch_template: Template,
+    pub worktree_template: Template,
+    pub hooks: Hooks,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize(Path::new("/home/user/myapp/main/../worktrees")),
+            PathBuf::from("/home/user/myapp/worktrees")
+        );
+        assert_eq!(
+            normalize(Path::new("/home/user/./myapp")),
+            PathBuf::from("/home/user/myapp")
+        );
+        assert_eq!(normalize(Path::new("/..")), PathBuf::from("/"));
+    }
+
+    #[test]
+    fn test_project_config_validation() {
+        let levels = Levels::builtin();
+
+        let config: ProjectConfig = toml::from_str(r#"level = "fix""#).unwrap();
+        assert!(config.validate(Path::new(".tm.toml"), &levels).is_ok());
+
+        let config: ProjectConfig = toml::from_str(r#"level = "bogus""#).unwrap();
+        assert!(config.validate(Path::new(".tm.toml"), &levels).is_err());
+    }
+}
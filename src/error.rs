@@ -1,3 +1,4 @@
+use crate::models::task::TaskStatus;
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -8,12 +9,27 @@ pub enum TmError {
     #[error("Task '{title}' already exists in project '{project}'")]
     DuplicateTask { project: String, title: String },
 
+    #[error("Task '{title}' is already archived in project '{project}'")]
+    DuplicateArchivedTask { project: String, title: String },
+
     #[error("Project '{project}' not found")]
     ProjectNotFound { project: String },
 
     #[error("Task '{title}' not found in project '{project}'")]
     TaskNotFound { project: String, title: String },
 
+    #[error("No task matches '{query}'")]
+    NoMatchingTask { query: String },
+
+    #[error("Not inside a task's worktree: {path}")]
+    NotInTask { path: PathBuf },
+
+    #[error("'{query}' matches several tasks: {}", candidates.join(", "))]
+    AmbiguousTask {
+        query: String,
+        candidates: Vec<String>,
+    },
+
     #[error("Worktree path does not exist: {path}")]
     WorktreePathNotFound { path: PathBuf },
 
@@ -26,7 +42,10 @@ pub enum TmError {
     #[error("Failed to remove worktree at {path}: {reason}")]
     WorktreeRemovalFailed { path: PathBuf, reason: String },
 
-    #[error("Worktree has uncommitted changes at {path}. Use --force to remove anyway.")]
+    #[error("Failed to move worktree at {path}: {reason}")]
+    WorktreeMoveFailed { path: PathBuf, reason: String },
+
+    #[error("Worktree has uncommitted changes at {path}. Use --force to go ahead anyway.")]
     WorktreeHasChanges { path: PathBuf },
 
     #[error("Git repository not found at {path}")]
@@ -35,9 +54,34 @@ pub enum TmError {
     #[error("Invalid main repository path: {path}")]
     InvalidMainRepoPath { path: PathBuf },
 
+    #[error("Base '{base}' does not resolve to a commit")]
+    BaseNotFound { base: String },
+
+    #[error("Branch '{branch}' not found locally or on any remote")]
+    BranchNotFound { branch: String },
+
     #[error("Worktree already exists at {path}")]
     WorktreeAlreadyExists { path: PathBuf },
 
+    #[error("Cannot change status of task '{title}' from {from} to {to}")]
+    InvalidStatusTransition {
+        title: String,
+        from: TaskStatus,
+        to: TaskStatus,
+    },
+
+    #[error("No main repository configured for project '{project}'")]
+    MissingMainRepo { project: String },
+
+    #[error("Invalid config in {path}: {reason}")]
+    InvalidConfig { path: PathBuf, reason: String },
+
+    #[error("Hook '{command}' failed: {reason}")]
+    HookFailed { command: String, reason: String },
+
+    #[error("Cancelled")]
+    Cancelled,
+
     #[error("Invalid input for {field}: {reason}")]
     InvalidInput { field: String, reason: String },
 
@@ -67,16 +111,43 @@ impl TmError {
                     title, project
                 )
             }
+            TmError::DuplicateArchivedTask { project, title } => {
+                format!(
+                    "A task named '{}' is already archived in project '{}'.\n\
+                    Use 'tm unarchive {} {}' to restore it, or a different title.",
+                    title, project, project, title
+                )
+            }
             TmError::TaskNotFound { project, title } => {
                 format!(
                     "Could not find task '{}' in project '{}'. Use 'tm list' to see available tasks.",
                     title, project
                 )
             }
+            TmError::NoMatchingTask { query } => {
+                format!(
+                    "No task matches '{}'. Use 'tm list' to see available tasks.",
+                    query
+                )
+            }
+            TmError::AmbiguousTask { query, candidates } => {
+                format!(
+                    "'{}' matches several tasks:\n  {}\nUse a longer prefix or <project>/<title>.",
+                    query,
+                    candidates.join("\n  ")
+                )
+            }
+            TmError::NotInTask { path } => {
+                format!(
+                    "'{}' is not inside the worktree of any task.\n\
+                    Pass the task explicitly, e.g. 'tm switch JIRA-123'.",
+                    path.display()
+                )
+            }
             TmError::WorktreeHasChanges { path } => {
                 format!(
                     "The worktree at '{}' has uncommitted changes.\n\
-                    Either commit or stash your changes, or use --force to remove anyway.",
+                    Either commit or stash your changes, or use --force to go ahead anyway.",
                     path.display()
                 )
             }
@@ -94,6 +165,43 @@ impl TmError {
                     path.display()
                 )
             }
+            TmError::BaseNotFound { base } => {
+                format!(
+                    "Could not find '{}' in the repository.\n\
+                    Use a local branch, a remote branch like origin/main, a tag or a commit SHA, \
+                    and pass --fetch if it only exists on the remote.",
+                    base
+                )
+            }
+            TmError::BranchNotFound { branch } => {
+                format!(
+                    "Could not find branch '{}' locally or on any remote.\n\
+                    Pass --fetch if it was pushed recently.",
+                    branch
+                )
+            }
+            TmError::InvalidStatusTransition { title, from, to } => {
+                format!(
+                    "Task '{}' cannot move from {} to {}.\n\
+                    Use --force to set the status anyway.",
+                    title, from, to
+                )
+            }
+            TmError::TomlDeserialization(e) => {
+                format!(
+                    "The task file could not be read: {}\n\
+                    Run 'tm restore --list' to see backups and 'tm restore' to recover.",
+                    e
+                )
+            }
+            TmError::MissingMainRepo { project } => {
+                format!(
+                    "No main repository is known for project '{}'.\n\
+                    Pass the path to the main repository, or configure it with \
+                    'tm project add {} <main_repo_path>'.",
+                    project, project
+                )
+            }
             TmError::InvalidInput { field, reason } => {
                 format!("Invalid {}: {}", field, reason)
             }
@@ -0,0 +1,21 @@
+use std::time::SystemTime;
+
+/// Human readable age of a file or commit, e.g. "5 minutes ago"
+pub fn format_age(modified: SystemTime) -> String {
+    let secs = SystemTime::now()
+        .duration_since(modified)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{} minutes ago", secs / 60),
+        3600..=86399 => format!("{} hours ago", secs / 3600),
+        _ => format!("{} days ago", secs / 86400),
+    }
+}
+
+/// First characters of a commit id, for display
+pub fn short_commit(commit: &str) -> &str {
+    &commit[..commit.len().min(7)]
+}
@@ -24,18 +24,32 @@ pub fn validate_worktree(path: &Path) -> TmResult<()> {
 pub fn has_uncommitted_changes(path: &Path) -> TmResult<bool> {
     let repo = Repository::open(path)?;
 
-    // Check for staged changes
-    let statuses = repo.statuses(None)?;
+    // Check for staged, unstaged and untracked changes, ignoring ignored files
+    let mut options = git2::StatusOptions::new();
+    options.include_untracked(true).include_ignored(false);
+    let statuses = repo.statuses(Some(&mut options))?;
 
     Ok(!statuses.is_empty())
 }
 
+/// Refuse a worktree with uncommitted changes, unless forced
+pub fn check_clean(path: &Path, force: bool) -> TmResult<()> {
+    if !force && has_uncommitted_changes(path)? {
+        return Err(TmError::WorktreeHasChanges {
+            path: path.to_path_buf(),
+        });
+    }
+    Ok(())
+}
+
 /// Create a new git worktree
+/// `base` can be anything git can resolve to a commit: a local branch, a remote-tracking
+/// branch like "origin/main", a tag or a commit SHA. Defaults to HEAD.
 pub fn create_worktree(
     main_repo_path: &Path,
     worktree_path: &Path,
     branch_name: &str,
-    base_branch: Option<&str>,
+    base: Option<&str>,
 ) -> TmResult<()> {
     // Open the main repository
     let repo = Repository::open(main_repo_path).map_err(|_| TmError::GitRepoNotFound {
@@ -43,10 +57,12 @@ pub fn create_worktree(
     })?;
 
     // Determine the base commit
-    let base_commit = if let Some(base) = base_branch {
-        // Find the base branch
-        let base_ref = repo.find_reference(&format!("refs/heads/{}", base))?;
-        base_ref.peel_to_commit()?
+    let base_commit = if let Some(base) = base {
+        repo.revparse_single(base)
+            .and_then(|obj| obj.peel_to_commit())
+            .map_err(|_| TmError::BaseNotFound {
+                base: base.to_string(),
+            })?
     } else {
         // Use HEAD
         repo.head()?.peel_to_commit()?
@@ -60,6 +76,75 @@ pub fn create_worktree(
             reason: format!("Failed to create branch: {}", e),
         })?;
 
+    // Create the worktree, deleting the branch again if that fails
+    if let Err(e) = add_worktree(&repo, worktree_path, &branch) {
+        let mut branch = branch;
+        let _ = branch.delete();
+        return Err(e);
+    }
+
+    // Track a same-named branch on the remote so push/pull work right away
+    if let Some(remote) = tracking_remote(&repo, base) {
+        let mut config = repo.config()?;
+        config.set_str(&format!("branch.{}.remote", branch_name), &remote)?;
+        config.set_str(
+            &format!("branch.{}.merge", branch_name),
+            &format!("refs/heads/{}", branch_name),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Create a worktree with an existing local branch checked out,
+/// or with a new local branch tracking `<remote>/<branch_name>` if there is no local one
+/// Returns whether a local branch was created
+pub fn checkout_worktree(
+    main_repo_path: &Path,
+    worktree_path: &Path,
+    branch_name: &str,
+) -> TmResult<bool> {
+    // Open the main repository
+    let repo = Repository::open(main_repo_path).map_err(|_| TmError::GitRepoNotFound {
+        path: main_repo_path.to_path_buf(),
+    })?;
+
+    if let Ok(branch) = repo.find_branch(branch_name, git2::BranchType::Local) {
+        add_worktree(&repo, worktree_path, &branch)?;
+        return Ok(false);
+    }
+
+    // Prefer origin, then any other remote that has the branch
+    let mut remotes: Vec<String> = repo.remotes()?.iter().flatten().map(String::from).collect();
+    remotes.sort_by_key(|r| r != "origin");
+
+    let remote_branch = remotes
+        .iter()
+        .map(|remote| format!("{}/{}", remote, branch_name))
+        .find_map(|name| repo.find_branch(&name, git2::BranchType::Remote).ok())
+        .ok_or_else(|| TmError::BranchNotFound {
+            branch: branch_name.to_string(),
+        })?;
+    let upstream = remote_branch.name()?.map(String::from);
+
+    let mut branch = repo
+        .branch(branch_name, &remote_branch.get().peel_to_commit()?, false)
+        .map_err(|e| TmError::WorktreeCreationFailed {
+            path: worktree_path.to_path_buf(),
+            reason: format!("Failed to create branch: {}", e),
+        })?;
+    branch.set_upstream(upstream.as_deref())?;
+
+    if let Err(e) = add_worktree(&repo, worktree_path, &branch) {
+        let _ = branch.delete();
+        return Err(e);
+    }
+
+    Ok(true)
+}
+
+/// Add a worktree for a local branch
+fn add_worktree(repo: &Repository, worktree_path: &Path, branch: &git2::Branch) -> TmResult<()> {
     // Get the worktree name (last component of path, used for .git/worktrees/<name>)
     // This must not contain slashes, so we use the directory name
     let worktree_name = worktree_path
@@ -70,7 +155,6 @@ pub fn create_worktree(
             reason: "Invalid worktree path".to_string(),
         })?;
 
-    // Create the worktree
     repo.worktree(
         worktree_name,
         worktree_path,
@@ -84,26 +168,88 @@ pub fn create_worktree(
     Ok(())
 }
 
+/// Remote a new branch should track: the remote of the base if it is a remote-tracking
+/// branch or has an upstream, otherwise "origin" if the repository has it
+fn tracking_remote(repo: &Repository, base: Option<&str>) -> Option<String> {
+    let from_base = base.and_then(|base| {
+        let reference = if let Ok(branch) = repo.find_branch(base, git2::BranchType::Remote) {
+            branch.into_reference()
+        } else {
+            let local = repo.find_branch(base, git2::BranchType::Local).ok()?;
+            local.upstream().ok()?.into_reference()
+        };
+        let remote = repo.branch_remote_name(reference.name()?).ok()?;
+        remote.as_str().map(String::from)
+    });
+
+    from_base.or_else(|| repo.find_remote("origin").ok().map(|_| "origin".to_string()))
+}
+
+/// Remote a base reference belongs to, e.g. "origin" for "origin/main"
+/// Falls back to "origin" for local branches, tags and SHAs
+pub fn remote_for_base(main_repo_path: &Path, base: Option<&str>) -> TmResult<String> {
+    let repo = Repository::open(main_repo_path).map_err(|_| TmError::GitRepoNotFound {
+        path: main_repo_path.to_path_buf(),
+    })?;
+
+    let remote = base
+        .and_then(|base| base.split_once('/'))
+        .map(|(remote, _)| remote)
+        .filter(|remote| repo.find_remote(remote).is_ok())
+        .unwrap_or("origin");
+
+    Ok(remote.to_string())
+}
+
+/// Fetch a remote, authenticating through ssh-agent or git credential helpers
+pub fn fetch_remote(main_repo_path: &Path, remote_name: &str) -> TmResult<()> {
+    let repo = Repository::open(main_repo_path).map_err(|_| TmError::GitRepoNotFound {
+        path: main_repo_path.to_path_buf(),
+    })?;
+    let config = repo.config()?;
+
+    let mut callbacks = git2::RemoteCallbacks::new();
+    callbacks.credentials(|url, username, allowed| {
+        if allowed.contains(git2::CredentialType::SSH_KEY) {
+            git2::Cred::ssh_key_from_agent(username.unwrap_or("git"))
+        } else if allowed.contains(git2::CredentialType::USER_PASS_PLAINTEXT) {
+            git2::Cred::credential_helper(&config, url, username)
+        } else {
+            git2::Cred::default()
+        }
+    });
+
+    let mut options = git2::FetchOptions::new();
+    options.remote_callbacks(callbacks);
+
+    let mut remote = repo.find_remote(remote_name)?;
+    remote.fetch::<&str>(&[], Some(&mut options), None)?;
+
+    Ok(())
+}
+
+/// Delete a local branch
+pub fn delete_branch(main_repo_path: &Path, branch_name: &str) -> TmResult<()> {
+    let repo = Repository::open(main_repo_path).map_err(|_| TmError::GitRepoNotFound {
+        path: main_repo_path.to_path_buf(),
+    })?;
+
+    repo.find_branch(branch_name, git2::BranchType::Local)?.delete()?;
+
+    Ok(())
+}
+
 /// Remove a git worktree
 pub fn remove_worktree(path: &Path, force: bool) -> TmResult<()> {
     // First check if it has uncommitted changes
-    if !force && has_uncommitted_changes(path)? {
-        return Err(TmError::WorktreeHasChanges {
-            path: path.to_path_buf(),
-        });
-    }
+    check_clean(path, force)?;
 
     // Open the worktree repository
     let repo = Repository::open(path)?;
 
     // Get the main repository path from the worktree and convert to owned PathBuf
-    let main_repo_path = repo
-        .path()
-        .parent()
-        .and_then(|p| p.parent())
-        .and_then(|p| p.parent())
-        .map(|p| p.to_path_buf())
-        .ok_or_else(|| TmError::WorktreeRemovalFailed {
+    let main_repo_path =
+        main_repo_path_of(&repo).ok_or_else(|| TmError::WorktreeRemovalFailed {
             path: path.to_path_buf(),
             reason: "Could not determine main repository path".to_string(),
         })?;
@@ -124,6 +270,7 @@ pub fn remove_worktree(path: &Path, force: bool) -> TmResult<()> {
     })?;
 
     // Prune invalid worktrees (this will clean up the removed worktree)
+    // Default prune options leave valid worktrees alone
     let worktrees = main_repo.worktrees().map_err(|e| TmError::WorktreeRemovalFailed {
         path: path.to_path_buf(),
         reason: format!("Failed to list worktrees: {}", e),
@@ -131,38 +278,180 @@ pub fn remove_worktree(path: &Path, force: bool) -> TmResult<()> {
 
     for wt_name in worktrees.iter().flatten() {
         if let Ok(wt) = main_repo.find_worktree(wt_name) {
-            let mut prune_options = git2::WorktreePruneOptions::new();
-            prune_options.valid(true);
-            let _ = wt.prune(Some(&mut prune_options));
+            let _ = wt.prune(None);
         }
     }
 
     Ok(())
 }
 
-/// Get information about a worktree
-#[allow(dead_code)]
-pub fn get_worktree_info(path: &Path) -> TmResult<WorktreeInfo> {
+/// Get information about a worktree, counting ahead/behind against its upstream,
+/// else `base` (the task's base), else the branch checked out in the main repository
+pub fn get_worktree_info(path: &Path, base: Option<&str>) -> TmResult<WorktreeInfo> {
+    if !path.exists() {
+        return Err(TmError::WorktreePathNotFound {
+            path: path.to_path_buf(),
+        });
+    }
+
     let repo = Repository::open(path)?;
 
     let head = repo.head()?;
-    let branch_name = head.shorthand().map(String::from);
+    let branch_name = if head.is_branch() {
+        head.shorthand().map(String::from)
+    } else {
+        None
+    };
+
+    let changes = changed_files(&repo)?;
+    let upstream = branch_name
+        .as_deref()
+        .and_then(|name| upstream_of(&repo, name));
+
+    // A task base that no longer resolves, like a deleted remote branch, is skipped
+    let task_base = base
+        .filter(|base| repo.revparse_single(base).is_ok())
+        .map(String::from);
+    let base = upstream
+        .clone()
+        .or(task_base)
+        .or_else(|| main_branch_of(&repo))
+        .filter(|base| Some(base) != branch_name.as_ref());
 
-    let has_changes = has_uncommitted_changes(path)?;
+    let (ahead, behind) = match (head.target(), base.as_deref()) {
+        (Some(head_oid), Some(base)) => {
+            let base_oid = repo.revparse_single(base)?.peel_to_commit()?.id();
+            repo.graph_ahead_behind(head_oid, base_oid)?
+        }
+        _ => (0, 0),
+    };
+
+    let head_commit = head.peel_to_commit().ok().map(|commit| CommitInfo {
+        id: commit.id().to_string(),
+        summary: commit.summary().unwrap_or_default().to_string(),
+        time: commit.time().seconds(),
+    });
 
     Ok(WorktreeInfo {
-        path: path.to_path_buf(),
         branch: branch_name,
-        has_uncommitted_changes: has_changes,
+        has_uncommitted_changes: !changes.is_empty(),
+        upstream,
+        base,
+        ahead,
+        behind,
+        head: head_commit,
+        changes,
     })
 }
 
+/// Staged, unstaged and untracked files, with `git status --short` codes
+fn changed_files(repo: &Repository) -> TmResult<Vec<FileChange>> {
+    let mut options = git2::StatusOptions::new();
+    options.include_untracked(true).include_ignored(false);
+
+    let mut changes = Vec::new();
+    for entry in repo.statuses(Some(&mut options))?.iter() {
+        let status = entry.status();
+        let code = if status.is_wt_new() && !status.is_index_new() {
+            "??".to_string()
+        } else {
+            let index = if status.is_index_new() {
+                'A'
+            } else if status.is_index_modified() || status.is_index_typechange() {
+                'M'
+            } else if status.is_index_deleted() {
+                'D'
+            } else if status.is_index_renamed() {
+                'R'
+            } else {
+                ' '
+            };
+            let worktree = if status.is_wt_modified() || status.is_wt_typechange() {
+                'M'
+            } else if status.is_wt_deleted() {
+                'D'
+            } else if status.is_conflicted() {
+                'U'
+            } else {
+                ' '
+            };
+            format!("{}{}", index, worktree)
+        };
+
+        changes.push(FileChange {
+            status: code,
+            path: entry.path().unwrap_or_default().to_string(),
+        });
+    }
+
+    Ok(changes)
+}
+
+/// Shorthand name of the upstream of a local branch (e.g. "origin/main")
+fn upstream_of(repo: &Repository, branch: &str) -> Option<String> {
+    let local = repo.find_branch(branch, git2::BranchType::Local).ok()?;
+    let upstream = local.upstream().ok()?;
+    upstream.name().ok().flatten().map(String::from)
+}
+
+/// Path of the main repository a linked worktree belongs to
+/// The worktree's git dir is <main_repo>/.git/worktrees/<name>
+pub fn main_repo_path_of(repo: &Repository) -> Option<PathBuf> {
+    if !repo.is_worktree() {
+        return repo.workdir().map(|p| p.to_path_buf());
+    }
+
+    repo.path()
+        .parent()
+        .and_then(|p| p.parent())
+        .and_then(|p| p.parent())
+        .map(|p| p.to_path_buf())
+}
+
+/// Branch checked out in the main repository that a worktree belongs to
+fn main_branch_of(repo: &Repository) -> Option<String> {
+    let main_repo = Repository::open(main_repo_path_of(repo)?).ok()?;
+    let head = main_repo.head().ok()?;
+    if head.is_branch() {
+        head.shorthand().map(String::from)
+    } else {
+        None
+    }
+}
+
 #[derive(Debug)]
-#[allow(dead_code)]
 pub struct WorktreeInfo {
-    pub path: PathBuf,
     pub branch: Option<String>,
     pub has_uncommitted_changes: bool,
+    /// Remote branch the worktree branch tracks, if it still exists
+    pub upstream: Option<String>,
+    /// Branch the ahead/behind counts are relative to
+    pub base: Option<String>,
+    /// Commits on the worktree branch that are not on the base
+    pub ahead: usize,
+    /// Commits on the base that are not on the worktree branch
+    pub behind: usize,
+    /// Commit checked out, None if HEAD is unborn
+    pub head: Option<CommitInfo>,
+    /// Uncommitted files
+    pub changes: Vec<FileChange>,
+}
+
+/// A commit as shown in task details
+#[derive(Debug)]
+pub struct CommitInfo {
+    pub id: String,
+    pub summary: String,
+    /// Commit time in seconds since the epoch
+    pub time: i64,
+}
+
+/// An uncommitted file in a worktree
+#[derive(Debug)]
+pub struct FileChange {
+    /// Two-letter code as in `git status --short`, e.g. "M ", " M" or "??"
+    pub status: String,
+    pub path: String,
 }
 
 /// Convert a name to snake_case (for filesystem paths)
@@ -205,18 +494,9 @@ pub fn get_repo_root(main_repo_path: &Path) -> TmResult<PathBuf> {
     Ok(parent.to_path_buf())
 }
 
-/// Compute worktree path from components
-/// Format: <repo_root>/<level>/<id>-<name_snake_case>
-pub fn compute_worktree_path(
-    main_repo_path: &Path,
-    level: &str,
-    id: &str,
-    name: &str,
-) -> TmResult<PathBuf> {
-    let repo_root = get_repo_root(main_repo_path)?;
-    let name_snake = to_snake_case(name);
-
-    Ok(repo_root.join(level).join(format!("{}-{}", id, name_snake)))
+/// Check whether a name can be used for a local branch
+pub fn is_valid_branch_name(name: &str) -> bool {
+    git2::Reference::is_valid_name(&format!("refs/heads/{}", name))
 }
 
 /// Generate branch name from components
@@ -226,6 +506,369 @@ pub fn generate_branch_name(level: &str, id: &str, name: &str) -> String {
     format!("{}/{}-{}", level, id, name_kebab)
 }
 
+/// Components of a branch name following the `<level>/<id>-<name>` scheme
+#[derive(Debug, PartialEq, Eq)]
+pub struct BranchComponents {
+    pub level: String,
+    pub id: String,
+    pub name: String,
+}
+
+/// Parse a branch name produced by `generate_branch_name` back into its components
+/// The ID extends up to and including the first dash-separated segment containing a digit,
+/// so "feature/JIRA-123-auth-system" -> ("feature", "JIRA-123", "auth-system")
+pub fn parse_branch_name(branch: &str) -> Option<BranchComponents> {
+    let (level, rest) = branch.split_once('/')?;
+    if level.is_empty() || rest.contains('/') {
+        return None;
+    }
+
+    let segments: Vec<&str> = rest.split('-').collect();
+    if segments.len() < 2 || segments.iter().any(|s| s.is_empty()) {
+        return None;
+    }
+
+    let id_end = segments
+        .iter()
+        .position(|s| s.chars().any(|c| c.is_ascii_digit()))
+        .unwrap_or(0);
+
+    // There must be at least one segment left over for the name
+    if id_end + 1 >= segments.len() {
+        return None;
+    }
+
+    Some(BranchComponents {
+        level: level.to_string(),
+        id: segments[..=id_end].join("-"),
+        name: segments[id_end + 1..].join("-"),
+    })
+}
+
+/// A worktree registered in a main repository
+#[derive(Debug)]
+pub struct WorktreeEntry {
+    /// Name of the worktree (.git/worktrees/<name>)
+    pub name: String,
+    pub path: PathBuf,
+    /// Checked out branch, None if HEAD is detached or the worktree is missing
+    pub branch: Option<String>,
+    /// Whether git considers the worktree entry stale (e.g. its directory is gone)
+    pub prunable: bool,
+}
+
+/// List all linked worktrees of a main repository
+pub fn list_worktrees(main_repo_path: &Path) -> TmResult<Vec<WorktreeEntry>> {
+    let repo = Repository::open(main_repo_path).map_err(|_| TmError::GitRepoNotFound {
+        path: main_repo_path.to_path_buf(),
+    })?;
+
+    let mut entries = Vec::new();
+    for name in repo.worktrees()?.iter().flatten() {
+        let wt = repo.find_worktree(name)?;
+        let branch = Repository::open_from_worktree(&wt)
+            .ok()
+            .and_then(|wt_repo| {
+                let head = wt_repo.head().ok()?;
+                if head.is_branch() {
+                    head.shorthand().map(String::from)
+                } else {
+                    None
+                }
+            });
+
+        entries.push(WorktreeEntry {
+            name: name.to_string(),
+            path: wt.path().to_path_buf(),
+            branch,
+            prunable: wt.is_prunable(None).unwrap_or(false),
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Prune a stale worktree entry from .git/worktrees
+pub fn prune_worktree(main_repo_path: &Path, name: &str) -> TmResult<()> {
+    let repo = Repository::open(main_repo_path).map_err(|_| TmError::GitRepoNotFound {
+        path: main_repo_path.to_path_buf(),
+    })?;
+
+    let wt = repo.find_worktree(name)?;
+    wt.prune(None)?;
+
+    Ok(())
+}
+
+/// List local branch names of a repository
+pub fn list_local_branches(main_repo_path: &Path) -> TmResult<Vec<String>> {
+    let repo = Repository::open(main_repo_path).map_err(|_| TmError::GitRepoNotFound {
+        path: main_repo_path.to_path_buf(),
+    })?;
+
+    let mut branches = Vec::new();
+    for branch in repo.branches(Some(git2::BranchType::Local))? {
+        let (branch, _) = branch?;
+        if let Some(name) = branch.name()? {
+            branches.push(name.to_string());
+        }
+    }
+
+    Ok(branches)
+}
+
+/// Branch checked out at a path, None if HEAD is detached or unborn
+pub fn current_branch(path: &Path) -> Option<String> {
+    let repo = Repository::open(path).ok()?;
+    let head = repo.head().ok()?;
+    if head.is_branch() {
+        head.shorthand().map(String::from)
+    } else {
+        None
+    }
+}
+
+/// Full id of the commit checked out at a path, None if HEAD is unborn
+pub fn head_commit(path: &Path) -> Option<String> {
+    let repo = Repository::open(path).ok()?;
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    Some(commit.id().to_string())
+}
+
+/// Time of the commit checked out at a path, in seconds since the epoch
+pub fn head_commit_time(path: &Path) -> Option<i64> {
+    let repo = Repository::open(path).ok()?;
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    Some(commit.time().seconds())
+}
+
+/// Where a task's own work on its branch starts
+#[derive(Debug, Clone, Copy)]
+pub enum WorkStart<'a> {
+    /// Commits after this one are the task's work
+    Commit(&'a str),
+    /// Commits made after this time (the task's creation) are the task's work,
+    /// for tasks stored before start commits were recorded
+    Time(i64),
+}
+
+/// Whether a branch tip has commits of its own after `start`
+fn has_work(tip: &git2::Commit, start: WorkStart) -> bool {
+    match start {
+        WorkStart::Commit(id) => tip.id().to_string() != id,
+        WorkStart::Time(time) => tip.time().seconds() > time,
+    }
+}
+
+/// Commit where a local branch forked off `base` (HEAD of the main repository if None)
+pub fn fork_point(main_repo_path: &Path, branch: &str, base: Option<&str>) -> Option<String> {
+    let repo = Repository::open(main_repo_path).ok()?;
+    let tip = repo
+        .find_branch(branch, git2::BranchType::Local)
+        .ok()?
+        .get()
+        .peel_to_commit()
+        .ok()?;
+    let base = repo
+        .revparse_single(base.unwrap_or("HEAD"))
+        .ok()?
+        .peel_to_commit()
+        .ok()?;
+    let oid = repo.merge_base(tip.id(), base.id()).ok()?;
+    Some(oid.to_string())
+}
+
+/// Whether a local branch has been merged into `base`: its tip is reachable from the base
+/// and it has work of its own after `start`
+/// Without `start`, a branch still pointing at the base counts as having no work
+/// Squash and rebase merges rewrite the commits and aren't detected
+pub fn is_merged(
+    main_repo_path: &Path,
+    branch: &str,
+    base: &str,
+    start: Option<WorkStart>,
+) -> TmResult<bool> {
+    let repo = Repository::open(main_repo_path).map_err(|_| TmError::GitRepoNotFound {
+        path: main_repo_path.to_path_buf(),
+    })?;
+
+    let tip = repo
+        .find_branch(branch, git2::BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let base_oid = repo
+        .revparse_single(base)
+        .and_then(|obj| obj.peel_to_commit())
+        .map_err(|_| TmError::BaseNotFound {
+            base: base.to_string(),
+        })?
+        .id();
+
+    let reachable = tip.id() == base_oid || repo.graph_descendant_of(base_oid, tip.id())?;
+    let has_work = match start {
+        Some(start) => has_work(&tip, start),
+        None => tip.id() != base_oid,
+    };
+
+    Ok(reachable && has_work)
+}
+
+/// Whether a local branch tracks a remote branch that no longer exists,
+/// like `[gone]` in `git branch -vv`
+/// New branches track their remote branch before the first push, so a branch only
+/// counts as gone if its work reached the remote: its tip is on one of the remote's
+/// branches and, with `start`, has commits of its own after it
+pub fn upstream_gone(main_repo_path: &Path, branch: &str, start: Option<WorkStart>) -> bool {
+    let Ok(repo) = Repository::open(main_repo_path) else {
+        return false;
+    };
+    let Ok(local) = repo.find_branch(branch, git2::BranchType::Local) else {
+        return false;
+    };
+    let Ok(config) = repo.config() else {
+        return false;
+    };
+    let (Ok(remote), Ok(_)) = (
+        config.get_string(&format!("branch.{}.remote", branch)),
+        config.get_string(&format!("branch.{}.merge", branch)),
+    ) else {
+        return false;
+    };
+    if local.upstream().is_ok() {
+        return false;
+    }
+
+    let Ok(tip) = local.get().peel_to_commit() else {
+        return false;
+    };
+    if start.is_some_and(|start| !has_work(&tip, start)) {
+        return false;
+    }
+    let Ok(remote_refs) = repo.references_glob(&format!("refs/remotes/{}/*", remote)) else {
+        return false;
+    };
+    let remote_tips: Vec<git2::Oid> = remote_refs.flatten().filter_map(|r| r.target()).collect();
+
+    remote_tips
+        .into_iter()
+        .any(|oid| oid == tip.id() || repo.graph_descendant_of(oid, tip.id()).unwrap_or(false))
+}
+
+/// Rename a local branch, carrying over its config and worktree HEADs like `git branch -m`
+/// An upstream pointing at the same-named remote branch is moved to the new name,
+/// so the next push creates the renamed branch on the remote
+pub fn rename_branch(main_repo_path: &Path, old_name: &str, new_name: &str) -> TmResult<()> {
+    let repo = Repository::open(main_repo_path).map_err(|_| TmError::GitRepoNotFound {
+        path: main_repo_path.to_path_buf(),
+    })?;
+
+    let mut branch = repo.find_branch(old_name, git2::BranchType::Local)?;
+    branch.rename(new_name, false)?;
+
+    let mut config = repo.config()?;
+    let merge_key = format!("branch.{}.merge", new_name);
+    if config.get_string(&merge_key).ok().as_deref() == Some(&format!("refs/heads/{}", old_name)) {
+        config.set_str(&merge_key, &format!("refs/heads/{}", new_name))?;
+    }
+
+    Ok(())
+}
+
+/// Move a linked worktree to a new directory, like `git worktree move`
+/// The directory is renamed and the links between it and its admin directory
+/// (`.git/worktrees/<name>`, which keeps its name) are rewritten
+pub fn move_worktree(old_path: &Path, new_path: &Path) -> TmResult<()> {
+    let failed = |reason: String| TmError::WorktreeMoveFailed {
+        path: old_path.to_path_buf(),
+        reason,
+    };
+
+    let repo = Repository::open(old_path)?;
+    if !repo.is_worktree() {
+        return Err(failed("not a linked worktree".to_string()));
+    }
+    let admin_dir = repo.path().to_path_buf();
+    drop(repo);
+
+    if new_path.exists() {
+        return Err(TmError::WorktreeAlreadyExists {
+            path: new_path.to_path_buf(),
+        });
+    }
+    if let Some(parent) = new_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::rename(old_path, new_path).map_err(|e| failed(e.to_string()))?;
+
+    let admin_dir = admin_dir.to_string_lossy();
+    let admin_dir = admin_dir.trim_end_matches('/');
+    let result = std::fs::write(new_path.join(".git"), format!("gitdir: {}\n", admin_dir))
+        .and_then(|()| {
+            std::fs::write(
+                Path::new(admin_dir).join("gitdir"),
+                format!("{}\n", new_path.join(".git").display()),
+            )
+        });
+    if let Err(e) = result {
+        let _ = std::fs::rename(new_path, old_path);
+        return Err(failed(e.to_string()));
+    }
+
+    Ok(())
+}
+
+/// One-line summaries ("abc1234 Fix login") of the latest commits on a worktree's HEAD
+pub fn recent_commits(path: &Path, count: usize) -> TmResult<Vec<String>> {
+    let repo = Repository::open(path)?;
+    let mut walk = repo.revwalk()?;
+    walk.push_head()?;
+
+    walk.take(count)
+        .map(|oid| {
+            let commit = repo.find_commit(oid?)?;
+            let id = commit.as_object().short_id()?;
+            Ok(format!(
+                "{} {}",
+                id.as_str().unwrap_or_default(),
+                commit.summary().unwrap_or_default()
+            ))
+        })
+        .collect()
+}
+
+/// Compare two paths, resolving symlinks where possible
+pub fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Path of the main repository for a worktree path
+pub fn find_main_repo(worktree_path: &Path) -> Option<PathBuf> {
+    let repo = Repository::open(worktree_path).ok()?;
+    main_repo_path_of(&repo)
+}
+
+/// A temporary directory `tm-<name>-<pid>` holding a repository in `main`
+/// with one empty commit, for tests
+#[cfg(test)]
+pub fn test_repo(name: &str) -> (PathBuf, Repository) {
+    let root = std::env::temp_dir().join(format!("tm-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let repo = Repository::init(root.join("main")).unwrap();
+    {
+        let sig = git2::Signature::now("t", "t@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+            .unwrap();
+    }
+    (root, repo)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -270,4 +913,161 @@ mod tests {
             "feature/83772-nem-plonn"
         );
     }
+
+    #[test]
+    fn test_parse_branch_name() {
+        assert_eq!(
+            parse_branch_name("feature/JIRA-123-auth-system"),
+            Some(BranchComponents {
+                level: "feature".to_string(),
+                id: "JIRA-123".to_string(),
+                name: "auth-system".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_branch_name("feature/83772-nem-plonn"),
+            Some(BranchComponents {
+                level: "feature".to_string(),
+                id: "83772".to_string(),
+                name: "nem-plonn".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_branch_name("chore/TASK-cleanup"),
+            Some(BranchComponents {
+                level: "chore".to_string(),
+                id: "TASK".to_string(),
+                name: "cleanup".to_string(),
+            })
+        );
+        assert_eq!(parse_branch_name("main"), None);
+        assert_eq!(parse_branch_name("feature/JIRA-123"), None);
+        assert_eq!(parse_branch_name("users/me/JIRA-1-thing"), None);
+    }
+
+    #[test]
+    fn test_rename_branch_and_move_worktree() {
+        let (root, repo) = test_repo("move");
+        let main = root.join("main");
+
+        let old_path = root.join("feature/JIRA-1-auth");
+        let new_path = root.join("fix/JIRA-2-login");
+        std::fs::create_dir_all(old_path.parent().unwrap()).unwrap();
+        create_worktree(&main, &old_path, "feature/JIRA-1-auth", None).unwrap();
+        let mut config = repo.config().unwrap();
+        config
+            .set_str(
+                "branch.feature/JIRA-1-auth.merge",
+                "refs/heads/feature/JIRA-1-auth",
+            )
+            .unwrap();
+
+        rename_branch(&main, "feature/JIRA-1-auth", "fix/JIRA-2-login").unwrap();
+        move_worktree(&old_path, &new_path).unwrap();
+
+        assert!(!old_path.exists());
+        assert_eq!(
+            current_branch(&new_path).as_deref(),
+            Some("fix/JIRA-2-login")
+        );
+        assert!(same_path(&find_main_repo(&new_path).unwrap(), &main));
+        assert_eq!(
+            repo.config()
+                .unwrap()
+                .get_string("branch.fix/JIRA-2-login.merge")
+                .unwrap(),
+            "refs/heads/fix/JIRA-2-login"
+        );
+        let worktrees = list_worktrees(&main).unwrap();
+        assert!(same_path(&worktrees[0].path, &new_path));
+        assert!(!worktrees[0].prunable);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_is_merged_and_upstream_gone() {
+        let (dir, repo) = test_repo("merged");
+        let root = dir.join("main");
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let commit = |branch: &str, time: i64, parents: &[&git2::Commit]| {
+            let sig =
+                git2::Signature::new("t", "t@example.com", &git2::Time::new(time, 0)).unwrap();
+            let oid = repo
+                .commit(None, &sig, &sig, "commit", &tree, parents)
+                .unwrap();
+            repo.reference(&format!("refs/{}", branch), oid, true, "test")
+                .unwrap();
+            repo.find_commit(oid).unwrap()
+        };
+
+        let branch = "feature/J-1-x";
+        let base = commit("heads/main", 1000, &[]);
+        commit(&format!("heads/{}", branch), 1000, &[]);
+        commit("remotes/origin/main", 1000, &[]);
+        repo.remote("origin", "https://example.com/repo.git")
+            .unwrap();
+        let mut config = repo.config().unwrap();
+        config
+            .set_str(&format!("branch.{}.remote", branch), "origin")
+            .unwrap();
+        config
+            .set_str(
+                &format!("branch.{}.merge", branch),
+                &format!("refs/heads/{}", branch),
+            )
+            .unwrap();
+        let start_id = fork_point(&root, branch, Some("main")).unwrap();
+        assert_eq!(start_id, base.id().to_string());
+        let start = Some(WorkStart::Commit(&start_id));
+
+        // A new branch without work of its own is neither merged nor gone
+        assert!(!is_merged(&root, branch, "main", start).unwrap());
+        assert!(!is_merged(&root, branch, "main", None).unwrap());
+        assert!(!upstream_gone(&root, branch, start));
+
+        // Committed but not pushed
+        let work = commit(&format!("heads/{}", branch), 3000, &[&base]);
+        assert!(!is_merged(&root, branch, "main", start).unwrap());
+        assert!(!upstream_gone(&root, branch, start));
+
+        // Pushed, then merged
+        repo.reference(
+            &format!("refs/remotes/origin/{}", branch),
+            work.id(),
+            true,
+            "test",
+        )
+        .unwrap();
+        repo.reference("refs/heads/main", work.id(), true, "test")
+            .unwrap();
+        repo.reference("refs/remotes/origin/main", work.id(), true, "test")
+            .unwrap();
+        assert!(is_merged(&root, branch, "main", start).unwrap());
+        assert!(!upstream_gone(&root, branch, start));
+
+        // The remote branch is deleted after merging
+        repo.find_reference(&format!("refs/remotes/origin/{}", branch))
+            .unwrap()
+            .delete()
+            .unwrap();
+        assert!(upstream_gone(&root, branch, start));
+        assert!(upstream_gone(&root, branch, Some(WorkStart::Time(2000))));
+        assert!(!upstream_gone(&root, branch, Some(WorkStart::Time(4000))));
+
+        // A branch that existed before its task, e.g. one checked out for review,
+        // merged with a merge commit: only its start commit tells its work apart
+        let colleague = "feature/PR-9-colleague";
+        let theirs = commit(&format!("heads/{}", colleague), 1500, &[&base]);
+        let review_start = fork_point(&root, colleague, Some("main")).unwrap();
+        let review_start = Some(WorkStart::Commit(&review_start));
+        assert!(!is_merged(&root, colleague, "main", review_start).unwrap());
+        commit("heads/main", 5000, &[&work, &theirs]);
+        assert!(is_merged(&root, colleague, "main", review_start).unwrap());
+        assert!(!is_merged(&root, colleague, "main", Some(WorkStart::Time(2000))).unwrap());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
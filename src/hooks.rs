@@ -0,0 +1,60 @@
+use crate::config::Config;
+use crate::error::{TmError, TmResult};
+use crate::models::task::Task;
+use std::path::Path;
+use std::process::Command;
+
+/// Task details exposed to hook commands as TM_* environment variables
+pub struct HookContext<'a> {
+    pub project: &'a str,
+    pub title: &'a str,
+    pub worktree_path: &'a Path,
+    pub main_repo_path: Option<&'a Path>,
+}
+
+/// Run hook commands through `sh -c` in the task's worktree, stopping at the first failure
+pub fn run(commands: &[String], ctx: &HookContext) -> TmResult<()> {
+    for command in commands {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg(command)
+            .current_dir(ctx.worktree_path)
+            .env("TM_PROJECT", ctx.project)
+            .env("TM_TASK", ctx.title)
+            .env("TM_WORKTREE", ctx.worktree_path);
+        if let Some(main_repo) = ctx.main_repo_path {
+            cmd.env("TM_MAIN_REPO", main_repo);
+        }
+
+        let status = cmd.status().map_err(|e| TmError::HookFailed {
+            command: command.clone(),
+            reason: e.to_string(),
+        })?;
+
+        if !status.success() {
+            return Err(TmError::HookFailed {
+                command: command.clone(),
+                reason: status.to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the project's pre-remove hooks in a task's worktree, before it is removed
+pub fn run_pre_remove(project: &str, task: &Task) -> TmResult<()> {
+    let config = Config::load()?;
+    let Some(project_config) = config.projects.get(project) else {
+        return Ok(());
+    };
+
+    let main_repo = task.main_repo();
+    let ctx = HookContext {
+        project,
+        title: &task.title,
+        worktree_path: &task.worktree_path,
+        main_repo_path: main_repo.as_deref(),
+    };
+    run(&project_config.hooks.pre_remove, &ctx)
+}
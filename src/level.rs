@@ -0,0 +1,243 @@
+use crate::config::LevelConfig;
+use crate::git;
+use colored::Color;
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+
+/// Levels available without any configuration, in display order
+pub const BUILTIN_LEVELS: &[&str] = &[
+    "feature", "fix", "chore", "docs", "refactor", "test", "perf", "style", "ci", "review",
+];
+
+/// Level used when neither the command line nor the project config sets one
+pub const DEFAULT_LEVEL: &str = "feature";
+
+/// Level used for `tm review` tasks
+pub const REVIEW_LEVEL: &str = "review";
+
+/// A kind of task, e.g. feature or fix
+/// Built-in levels can be overridden and new ones added in `[levels.<name>]` of config.toml
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Level {
+    name: String,
+    prefix: String,
+    dir: String,
+    color: Option<Color>,
+    aliases: Vec<String>,
+}
+
+impl Level {
+    fn builtin(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            prefix: name.to_string(),
+            dir: name.to_string(),
+            color: None,
+            aliases: Vec::new(),
+        }
+    }
+
+    /// Lowercase name, as used in task titles
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+
+    /// First component of branch names, `{level}` in branch templates
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Directory name, `{level}` in worktree templates
+    pub fn dir(&self) -> &str {
+        &self.dir
+    }
+
+    /// Color used for task titles of this level
+    pub fn color(&self) -> Option<Color> {
+        self.color
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+            || self.aliases.iter().any(|a| a.eq_ignore_ascii_case(name))
+    }
+}
+
+/// The set of levels known to tm
+#[derive(Debug, Clone)]
+pub struct Levels(Vec<Level>);
+
+impl Levels {
+    /// The built-in levels only
+    pub fn builtin() -> Self {
+        Self(
+            BUILTIN_LEVELS
+                .iter()
+                .map(|name| Level::builtin(name))
+                .collect(),
+        )
+    }
+
+    /// The built-in levels merged with the `[levels]` table of config.toml
+    /// Entries named like a built-in level override it, others are appended
+    pub fn from_config(config: &BTreeMap<String, LevelConfig>) -> Result<Self, String> {
+        let mut levels = Self::builtin().0;
+
+        for (name, entry) in config {
+            check_component(name, "name", name)?;
+
+            let prefix = entry.prefix.clone().unwrap_or_else(|| name.clone());
+            check_component(name, "prefix", &prefix)?;
+
+            let dir = entry.dir.clone().unwrap_or_else(|| name.clone());
+            if !matches!(
+                Path::new(&dir).components().collect::<Vec<_>>()[..],
+                [Component::Normal(_)]
+            ) {
+                return Err(format!(
+                    "level '{}': dir '{}' must be a single directory name",
+                    name, dir
+                ));
+            }
+
+            let color = match entry.color {
+                Some(ref color) => Some(
+                    color
+                        .parse::<Color>()
+                        .map_err(|()| format!("level '{}': unknown color '{}'", name, color))?,
+                ),
+                None => None,
+            };
+
+            let level = Level {
+                name: name.to_lowercase(),
+                prefix,
+                dir,
+                color,
+                aliases: entry.aliases.clone(),
+            };
+
+            match levels.iter_mut().find(|l| l.name == level.name) {
+                Some(existing) => *existing = level,
+                None => levels.push(level),
+            }
+        }
+
+        // Names, aliases and prefixes must each identify a single level
+        for (i, level) in levels.iter().enumerate() {
+            for other in &levels[i + 1..] {
+                let clash = std::iter::once(&level.name)
+                    .chain(&level.aliases)
+                    .find(|n| other.matches(n));
+                if let Some(clash) = clash {
+                    return Err(format!(
+                        "'{}' is used by both level '{}' and level '{}'",
+                        clash, level.name, other.name
+                    ));
+                }
+                if level.prefix == other.prefix {
+                    return Err(format!(
+                        "levels '{}' and '{}' have the same branch prefix '{}'",
+                        level.name, other.name, level.prefix
+                    ));
+                }
+            }
+        }
+
+        Ok(Self(levels))
+    }
+
+    /// Look up a level by name or alias, ignoring case
+    pub fn find(&self, name: &str) -> Option<&Level> {
+        self.0.iter().find(|level| level.matches(name))
+    }
+
+    /// Look up the level whose branch prefix is `prefix`
+    pub fn by_prefix(&self, prefix: &str) -> Option<&Level> {
+        self.0.iter().find(|level| level.prefix == prefix)
+    }
+
+    /// The level used when none is given
+    pub fn default_level(&self) -> &Level {
+        self.find(DEFAULT_LEVEL)
+            .expect("built-in levels are never removed")
+    }
+
+    /// The level used for review tasks
+    pub fn review(&self) -> &Level {
+        self.find(REVIEW_LEVEL)
+            .expect("built-in levels are never removed")
+    }
+
+    /// Names of all levels, for error messages
+    pub fn names(&self) -> Vec<&str> {
+        self.0.iter().map(|level| level.as_str()).collect()
+    }
+}
+
+/// Check that a level value can be used as a single branch and path component
+fn check_component(level: &str, field: &str, value: &str) -> Result<(), String> {
+    if value.contains('/') || !git::is_valid_branch_name(value) {
+        return Err(format!(
+            "level '{}': {} '{}' must be a single valid branch name component",
+            level, field, value
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(toml: &str) -> BTreeMap<String, LevelConfig> {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn test_builtin_levels() {
+        let levels = Levels::builtin();
+        assert_eq!(levels.names(), BUILTIN_LEVELS);
+        assert_eq!(levels.find("Fix").unwrap().prefix(), "fix");
+        assert_eq!(levels.review().as_str(), "review");
+        assert!(levels.find("hotfix").is_none());
+    }
+
+    #[test]
+    fn test_custom_levels() {
+        let levels = Levels::from_config(&config(
+            r#"
+            [feature]
+            prefix = "feat"
+            aliases = ["f"]
+
+            [hotfix]
+            dir = "hotfixes"
+            color = "red"
+            aliases = ["hf"]
+            "#,
+        ))
+        .unwrap();
+
+        let feature = levels.find("f").unwrap();
+        assert_eq!(feature.as_str(), "feature");
+        assert_eq!(feature.prefix(), "feat");
+        assert_eq!(feature.dir(), "feature");
+        assert_eq!(levels.by_prefix("feat").unwrap().as_str(), "feature");
+
+        let hotfix = levels.find("HF").unwrap();
+        assert_eq!(hotfix.prefix(), "hotfix");
+        assert_eq!(hotfix.dir(), "hotfixes");
+        assert_eq!(hotfix.color(), Some(Color::Red));
+        assert_eq!(levels.names().last(), Some(&"hotfix"));
+    }
+
+    #[test]
+    fn test_invalid_levels() {
+        assert!(Levels::from_config(&config("[\"a/b\"]")).is_err());
+        assert!(Levels::from_config(&config("[bug]\ndir = \"../bug\"")).is_err());
+        assert!(Levels::from_config(&config("[bug]\ncolor = \"plaid\"")).is_err());
+        assert!(Levels::from_config(&config("[bug]\naliases = [\"fix\"]")).is_err());
+        assert!(Levels::from_config(&config("[bug]\nprefix = \"fix\"")).is_err());
+    }
+}
@@ -1,12 +1,18 @@
 use clap::Parser;
-use cli::{Cli, Commands};
+use cli::{Cli, Commands, ProjectCommands};
 
 mod cli;
 mod commands;
 mod config;
 mod error;
+mod format;
 mod git;
+mod hooks;
+mod level;
 mod models;
+mod picker;
+mod prompt;
+mod template;
 
 fn main() {
     let cli = Cli::parse();
@@ -19,6 +25,9 @@ fn main() {
             id,
             name,
             description,
+            base,
+            from_branch,
+            fetch,
             remote_url,
             api_url,
         } => commands::add::execute(
@@ -28,17 +37,145 @@ fn main() {
             id,
             name,
             description,
+            base,
+            from_branch,
+            fetch,
             remote_url,
             api_url,
         ),
-        Commands::List { project, format } => commands::list::execute(project, format),
+        Commands::List {
+            project,
+            state,
+            level,
+            reference,
+            path,
+            dirty,
+            status,
+            format,
+            archived,
+            sort,
+            columns,
+        } => commands::list::execute(
+            commands::list::Filters {
+                project,
+                state,
+                level,
+                reference,
+                path,
+                dirty,
+            },
+            status,
+            format,
+            archived,
+            sort,
+            columns,
+        ),
+        Commands::Log {
+            task,
+            all,
+            limit,
+            json,
+        } => commands::log::execute(task.selector(), all, limit, json),
         Commands::Remove {
+            task,
+            remove_worktree,
+            force,
+        } => commands::remove::execute(task.selector(), remove_worktree, force),
+        Commands::Archive {
+            task,
+            remove_worktree,
+            force,
+        } => commands::archive::execute(task.selector(), remove_worktree, force),
+        Commands::Unarchive { task } => commands::archive::unarchive(task.selector()),
+        Commands::Complete { words } => commands::complete::execute(words),
+        Commands::Completions { shell } => commands::complete::completions(shell),
+        Commands::Show { task, json } => commands::show::execute(task.selector(), json),
+        Commands::Current { json, short, quiet } => commands::current::execute(json, short, quiet),
+        Commands::Doctor { fix } => commands::doctor::execute(fix),
+        Commands::Init { shell, no_prompt } => commands::init::execute(shell, !no_prompt),
+        Commands::Import {
+            project,
+            main_repo_path,
+            dry_run,
+        } => commands::import::execute(project, main_repo_path, dry_run),
+        Commands::Project(command) => match command {
+            ProjectCommands::Add {
+                name,
+                main_repo,
+                base_branch,
+                level,
+                worktree_root,
+                branch_template,
+                worktree_template,
+                post_create,
+                pre_remove,
+            } => commands::project::add(
+                name,
+                main_repo,
+                base_branch,
+                level,
+                worktree_root,
+                branch_template,
+                worktree_template,
+                post_create,
+                pre_remove,
+            ),
+            ProjectCommands::List => commands::project::list(),
+            ProjectCommands::Show { name } => commands::project::show(name),
+            ProjectCommands::Remove { name } => commands::project::remove(name),
+            ProjectCommands::Rename { name, new_name } => commands::project::rename(name, new_name),
+        },
+        Commands::Prune {
+            project,
+            stale_days,
+            gone,
+            yes,
+            dry_run,
+            force,
+        } => commands::prune::execute(project, stale_days, gone, yes, dry_run, force),
+        Commands::Restore { backup, list } => commands::restore::execute(backup, list),
+        Commands::Review {
             project,
+            branch,
+            main_repo,
+            description,
+            fetch,
+        } => commands::review::execute(project, branch, main_repo, description, fetch),
+        Commands::Status { args, force } => commands::status::execute(args, force),
+        Commands::Mv {
+            from_project,
             title,
-            remove_worktree,
+            to_project,
+        } => commands::mv::execute(from_project, title, to_project),
+        Commands::Rename {
+            task,
+            level,
+            id,
+            name,
             force,
-        } => commands::remove::execute(project, title, remove_worktree, force),
-        Commands::Switch { project, title } => commands::switch::execute(project, title),
+            keep_worktree,
+        } => commands::rename::execute(task.selector(), level, id, name, force, keep_worktree),
+        Commands::Edit {
+            task,
+            description,
+            reference,
+            remote_url,
+            api_url,
+        } => commands::edit::execute(
+            task.selector(),
+            commands::edit::Fields {
+                description,
+                reference,
+                remote_url,
+                api_url,
+            },
+        ),
+        Commands::Pick => commands::switch::pick(),
+        Commands::Switch { task } => match task.task {
+            Some(_) => commands::switch::execute(task.selector()),
+            None => commands::switch::pick(),
+        },
+        Commands::Ui => commands::ui::execute(),
     };
 
     if let Err(e) = result {
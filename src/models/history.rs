@@ -0,0 +1,125 @@
+use crate::config::get_history_file_path;
+use crate::error::TmResult;
+use crate::models::task::TaskStatus;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+/// What happened to a task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum EventKind {
+    Created,
+    Switched,
+    StatusChanged { from: TaskStatus, to: TaskStatus },
+    Edited { fields: Vec<String> },
+    Renamed { from: String },
+    Moved { from: String },
+    Archived,
+    Unarchived,
+    Removed,
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventKind::Created => f.write_str("created"),
+            EventKind::Switched => f.write_str("switched"),
+            EventKind::StatusChanged { from, to } => write!(f, "status {} -> {}", from, to),
+            EventKind::Edited { fields } => write!(f, "edited {}", fields.join(", ")),
+            EventKind::Renamed { from } => write!(f, "renamed from {}", from),
+            EventKind::Moved { from } => write!(f, "moved from project {}", from),
+            EventKind::Archived => f.write_str("archived"),
+            EventKind::Unarchived => f.write_str("unarchived"),
+            EventKind::Removed => f.write_str("removed"),
+        }
+    }
+}
+
+/// One line of the history file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Event {
+    pub at: DateTime<Utc>,
+    pub project: String,
+    pub title: String,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+/// Append an event to a history file
+pub fn append(path: &Path, event: &Event) -> TmResult<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut line = serde_json::to_string(event)?;
+    line.push('\n');
+
+    // A single append-mode write keeps lines from concurrent tm processes intact
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Record an event for a task that was just changed and saved
+/// History is secondary to the task itself, so a failure only prints a warning
+pub fn record(project: &str, title: &str, kind: EventKind) {
+    let event = Event {
+        at: Utc::now(),
+        project: project.to_string(),
+        title: title.to_string(),
+        kind,
+    };
+
+    if let Err(e) = get_history_file_path().and_then(|path| append(&path, &event)) {
+        eprintln!("Warning: could not record task history: {}", e);
+    }
+}
+
+/// Read all events, oldest first
+/// Lines that don't parse, e.g. events written by a newer tm, are skipped
+pub fn load(path: &Path) -> TmResult<Vec<Event>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = std::fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_roundtrip() {
+        let path = std::env::temp_dir().join(format!("tm-history-{}.jsonl", std::process::id()));
+        let event = Event {
+            at: "2024-05-01T12:00:00Z".parse().unwrap(),
+            project: "api".to_string(),
+            title: "feature/JIRA-1-auth".to_string(),
+            kind: EventKind::StatusChanged {
+                from: TaskStatus::Todo,
+                to: TaskStatus::InProgress,
+            },
+        };
+
+        append(&path, &event).unwrap();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, r#"{{"event":"from-the-future"}}"#).unwrap();
+        let events = load(&path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(content.starts_with(
+            r#"{"at":"2024-05-01T12:00:00Z","project":"api","title":"feature/JIRA-1-auth","event":"status-changed","from":"todo","to":"in-progress"}"#
+        ));
+        assert_eq!(events, vec![event]);
+    }
+}
@@ -1,2 +1,4 @@
+pub mod history;
+pub mod selector;
 pub mod storage;
 pub mod task;
@@ -0,0 +1,288 @@
+use crate::error::{TmError, TmResult};
+use crate::models::storage::TaskStorage;
+use crate::models::task::Task;
+use std::path::{Path, PathBuf};
+
+/// How a command refers to a single task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskSelector {
+    /// A query matched against tasks of one project
+    InProject { project: String, query: String },
+    /// A query matched against tasks of all projects
+    Query(String),
+    /// The task whose worktree contains the current directory
+    Current,
+}
+
+impl TaskSelector {
+    /// Build a selector from `<task>` or `<project> <title>` arguments,
+    /// falling back to the current task when neither is given
+    pub fn new(task: Option<String>, title: Option<String>) -> Self {
+        match (task, title) {
+            (Some(project), Some(query)) => TaskSelector::InProject { project, query },
+            (Some(query), None) => TaskSelector::Query(query),
+            (None, _) => TaskSelector::Current,
+        }
+    }
+}
+
+/// How closely a query matches a task, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Match {
+    Exact,
+    Prefix,
+    Substring,
+}
+
+/// Match a query against a task's names, ignoring case
+/// A task is known by `project/title`, its title, its title without the level
+/// (`JIRA-123-auth` for `feature/JIRA-123-auth`) and its reference
+fn match_task(query: &str, project: &str, task: &Task) -> Option<Match> {
+    let query = query.to_lowercase();
+    let title = task.title.to_lowercase();
+    let mut names = vec![format!("{}/{}", project.to_lowercase(), title)];
+    if let Some((_, rest)) = title.split_once('/') {
+        names.push(rest.to_string());
+    }
+    if let Some(ref reference) = task.reference {
+        names.push(reference.to_lowercase());
+    }
+    names.push(title);
+
+    names
+        .iter()
+        .filter_map(|name| {
+            if *name == query {
+                Some(Match::Exact)
+            } else if name.starts_with(&query) {
+                Some(Match::Prefix)
+            } else if name.contains(&query) {
+                Some(Match::Substring)
+            } else {
+                None
+            }
+        })
+        .min()
+}
+
+/// Resolve a selector against a list of tasks, see `TaskStorage::resolve`
+/// `has_project` tells whether a project exists for `TaskSelector::InProject`
+fn resolve_in(
+    selector: &TaskSelector,
+    tasks: Vec<(&str, &Task)>,
+    has_project: impl Fn(&str) -> bool,
+) -> TmResult<(String, String)> {
+    let (project_filter, query) = match selector {
+        TaskSelector::InProject { project, query } => {
+            if !has_project(project) {
+                return Err(TmError::ProjectNotFound {
+                    project: project.clone(),
+                });
+            }
+            (Some(project.as_str()), query)
+        }
+        TaskSelector::Query(query) => (None, query),
+        TaskSelector::Current => {
+            let cwd = std::env::current_dir()?;
+            return innermost_at(&cwd, tasks)
+                .map(|(project, task)| (project.to_string(), task.title.clone()))
+                .ok_or(TmError::NotInTask { path: cwd });
+        }
+    };
+
+    let matches: Vec<(Match, &str, &Task)> = tasks
+        .into_iter()
+        .filter(|(project, _)| project_filter.is_none_or(|filter| *project == filter))
+        .filter_map(|(project, task)| match_task(query, project, task).map(|m| (m, project, task)))
+        .collect();
+
+    let Some(best) = matches.iter().map(|(m, _, _)| *m).min() else {
+        return Err(match selector {
+            TaskSelector::InProject { project, query } => TmError::TaskNotFound {
+                project: project.clone(),
+                title: query.clone(),
+            },
+            _ => TmError::NoMatchingTask {
+                query: query.clone(),
+            },
+        });
+    };
+
+    let mut best: Vec<(&str, &Task)> = matches
+        .into_iter()
+        .filter(|(m, _, _)| *m == best)
+        .map(|(_, project, task)| (project, task))
+        .collect();
+
+    if best.len() > 1 {
+        let mut candidates: Vec<String> = best
+            .iter()
+            .map(|(project, task)| format!("{}/{}", project, task.title))
+            .collect();
+        candidates.sort();
+        return Err(TmError::AmbiguousTask {
+            query: query.to_string(),
+            candidates,
+        });
+    }
+
+    let (project, task) = best.remove(0);
+    Ok((project.to_string(), task.title.clone()))
+}
+
+/// The task whose worktree contains `path`, comparing canonical paths so
+/// symlinks on either side resolve to the same directory
+/// With nested worktrees the innermost one wins
+fn innermost_at<'a>(path: &Path, tasks: Vec<(&'a str, &'a Task)>) -> Option<(&'a str, &'a Task)> {
+    let path = path.canonicalize().ok()?;
+
+    tasks
+        .into_iter()
+        .filter_map(|(project, task)| {
+            let worktree: PathBuf = task.worktree_path.canonicalize().ok()?;
+            path.starts_with(&worktree)
+                .then(|| (worktree.components().count(), project, task))
+        })
+        .max_by_key(|(depth, _, _)| *depth)
+        .map(|(_, project, task)| (project, task))
+}
+
+impl TaskStorage {
+    /// Resolve a selector to the project and title of a single task
+    /// Exact matches win over prefix matches, which win over substring matches;
+    /// several tasks matching equally well is an error listing them
+    pub fn resolve(&self, selector: &TaskSelector) -> TmResult<(String, String)> {
+        resolve_in(selector, self.list_tasks(None), |project| {
+            self.projects.contains_key(project)
+        })
+    }
+
+    /// Resolve a selector to the project and title of a single archived task
+    pub fn resolve_archived(&self, selector: &TaskSelector) -> TmResult<(String, String)> {
+        let tasks = self
+            .list_archived(None)
+            .into_iter()
+            .map(|(project, archived)| (project, &archived.task))
+            .collect();
+        resolve_in(selector, tasks, |project| {
+            self.archived.contains_key(project)
+        })
+    }
+
+    /// Find the task whose worktree contains `path`, see `innermost_at`
+    pub fn task_at(&self, path: &Path) -> Option<(&str, &Task)> {
+        innermost_at(path, self.list_tasks(None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn storage() -> TaskStorage {
+        let mut storage = TaskStorage::new();
+        for (project, title, reference) in [
+            ("api", "feature/JIRA-123-auth-system", "JIRA-123"),
+            ("api", "fix/JIRA-1234-login", "JIRA-1234"),
+            ("web", "feature/WEB-7-auth-page", "WEB-7"),
+        ] {
+            let task = Task::new(title.to_string(), PathBuf::from(title))
+                .with_reference(reference.to_string());
+            storage.add_task(project.to_string(), task).unwrap();
+        }
+        storage
+    }
+
+    fn resolve(selector: TaskSelector) -> TmResult<(String, String)> {
+        storage().resolve(&selector)
+    }
+
+    fn query(query: &str) -> TaskSelector {
+        TaskSelector::Query(query.to_string())
+    }
+
+    #[test]
+    fn test_resolve_exact() {
+        let expected = (
+            "api".to_string(),
+            "feature/JIRA-123-auth-system".to_string(),
+        );
+
+        // The reference also prefixes JIRA-1234, but the exact match wins
+        assert_eq!(resolve(query("JIRA-123")).unwrap(), expected);
+        assert_eq!(resolve(query("jira-123")).unwrap(), expected);
+        assert_eq!(
+            resolve(query("api/feature/JIRA-123-auth-system")).unwrap(),
+            expected
+        );
+        assert_eq!(
+            resolve(TaskSelector::new(
+                Some("api".to_string()),
+                Some("feature/JIRA-123-auth-system".to_string())
+            ))
+            .unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_resolve_prefix_and_substring() {
+        assert_eq!(resolve(query("fix/")).unwrap().1, "fix/JIRA-1234-login");
+        assert_eq!(resolve(query("WEB")).unwrap().1, "feature/WEB-7-auth-page");
+        assert_eq!(resolve(query("login")).unwrap().1, "fix/JIRA-1234-login");
+        assert_eq!(
+            resolve(TaskSelector::new(
+                Some("web".to_string()),
+                Some("auth".to_string())
+            ))
+            .unwrap()
+            .1,
+            "feature/WEB-7-auth-page"
+        );
+    }
+
+    #[test]
+    fn test_resolve_errors() {
+        match resolve(query("auth")) {
+            Err(TmError::AmbiguousTask { candidates, .. }) => assert_eq!(
+                candidates,
+                [
+                    "api/feature/JIRA-123-auth-system",
+                    "web/feature/WEB-7-auth-page"
+                ]
+            ),
+            other => panic!("expected ambiguity, got {:?}", other),
+        }
+        assert!(matches!(
+            resolve(query("nothing")),
+            Err(TmError::NoMatchingTask { .. })
+        ));
+        assert!(matches!(
+            resolve(TaskSelector::new(
+                Some("mobile".to_string()),
+                Some("x".to_string())
+            )),
+            Err(TmError::ProjectNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_task_at_follows_symlinks() {
+        let root = std::env::temp_dir().join(format!("tm-task-at-{}", std::process::id()));
+        let worktree = root.join("feature").join("JIRA-1-auth");
+        std::fs::create_dir_all(worktree.join("src")).unwrap();
+        std::os::unix::fs::symlink(&worktree, root.join("link")).unwrap();
+
+        let mut storage = TaskStorage::new();
+        let task = Task::new("feature/JIRA-1-auth".to_string(), root.join("link"));
+        storage.add_task("api".to_string(), task).unwrap();
+
+        let found = storage.task_at(&worktree.join("src")).map(|(p, _)| p);
+        let outside = storage.task_at(&root).map(|(p, _)| p);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(found, Some("api"));
+        assert_eq!(outside, None);
+    }
+}
@@ -1,21 +1,87 @@
 use crate::error::TmResult;
-use crate::models::task::Task;
+use crate::models::task::{ArchivedTask, Task};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Number of previous versions of the tasks file kept as backups
+pub const BACKUP_COUNT: usize = 5;
+
+/// Append a suffix to a path, e.g. "tasks.toml" -> "tasks.toml.lock"
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Path of the n-th backup of the tasks file (1 is the most recent)
+pub fn backup_path(path: &Path, n: usize) -> PathBuf {
+    with_suffix(path, &format!(".bak.{}", n))
+}
+
+/// Write a file through a temporary file that is renamed into place,
+/// so a crash never leaves a truncated file behind
+pub fn write_atomic(path: &Path, content: &str) -> TmResult<()> {
+    let tmp_path = with_suffix(path, ".tmp");
+    let mut tmp = File::create(&tmp_path)?;
+    tmp.write_all(content.as_bytes())?;
+    tmp.sync_all()?;
+
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// Exclusive advisory lock on the tasks file, released when dropped
+pub struct StorageLock {
+    _file: File,
+}
+
+impl StorageLock {
+    /// Acquire the lock, waiting for other tm processes to finish
+    pub fn acquire(path: &Path) -> TmResult<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(with_suffix(path, ".lock"))?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                eprintln!("Waiting for another tm process to release the task file...");
+                file.lock()?;
+            }
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+
+        Ok(Self { _file: file })
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct TaskStorage {
-    /// Tasks grouped by project name
+    /// Tasks grouped by project name, ordered by name so listings and the file are stable
     #[serde(default)]
-    pub projects: HashMap<String, Vec<Task>>,
+    pub projects: BTreeMap<String, Vec<Task>>,
+
+    /// Archived tasks grouped by project name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub archived: BTreeMap<String, Vec<ArchivedTask>>,
 }
 
 impl TaskStorage {
     /// Create new empty storage
     pub fn new() -> Self {
         Self {
-            projects: HashMap::new(),
+            projects: BTreeMap::new(),
+            archived: BTreeMap::new(),
         }
     }
 
@@ -30,20 +96,64 @@ impl TaskStorage {
         Ok(storage)
     }
 
+    /// Lock the tasks file and load it, for commands that modify storage
+    /// The lock must be held until after `save`
+    pub fn load_locked(path: &PathBuf) -> TmResult<(Self, StorageLock)> {
+        let lock = StorageLock::acquire(path)?;
+        let storage = Self::load(path)?;
+        Ok((storage, lock))
+    }
+
     /// Save storage to TOML file
-    pub fn save(&self, path: &PathBuf) -> TmResult<()> {
+    /// Written atomically, keeping the previous versions as rotating backups
+    pub fn save(&self, path: &Path) -> TmResult<()> {
+        self.write(path, true)
+    }
+
+    /// Save storage without rotating backups, for bookkeeping such as the last switch
+    /// time that would otherwise push real changes out of the backups
+    pub fn save_without_backup(&self, path: &Path) -> TmResult<()> {
+        self.write(path, false)
+    }
+
+    fn write(&self, path: &Path, backup: bool) -> TmResult<()> {
         // Ensure parent directory exists
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
 
         let content = toml::to_string_pretty(self)?;
-        std::fs::write(path, content)?;
+
+        if backup && path.exists() {
+            Self::rotate_backups(path)?;
+        }
+
+        write_atomic(path, &content)
+    }
+
+    /// Shift existing backups up by one and copy the current file to backup 1
+    fn rotate_backups(path: &Path) -> TmResult<()> {
+        for n in (1..BACKUP_COUNT).rev() {
+            let from = backup_path(path, n);
+            if from.exists() {
+                std::fs::rename(&from, backup_path(path, n + 1))?;
+            }
+        }
+
+        std::fs::copy(path, backup_path(path, 1))?;
         Ok(())
     }
 
     /// Add a task to a project
+    /// Titles must be unique among the project's active and archived tasks
     pub fn add_task(&mut self, project: String, task: Task) -> TmResult<()> {
+        if self.get_archived(&project, &task.title).is_ok() {
+            return Err(crate::error::TmError::DuplicateArchivedTask {
+                project,
+                title: task.title.clone(),
+            });
+        }
+
         let tasks = self.projects.entry(project.clone()).or_default();
 
         // Check for duplicate title
@@ -96,6 +206,76 @@ impl TaskStorage {
             })
     }
 
+    /// Get a mutable task by project and title
+    pub fn get_task_mut(&mut self, project: &str, title: &str) -> TmResult<&mut Task> {
+        self.projects
+            .get_mut(project)
+            .and_then(|tasks| tasks.iter_mut().find(|t| t.title == title))
+            .ok_or_else(|| crate::error::TmError::TaskNotFound {
+                project: project.to_string(),
+                title: title.to_string(),
+            })
+    }
+
+    /// Check that no active or archived task in a project has this title
+    fn check_title_free(&self, project: &str, title: &str) -> TmResult<()> {
+        if self.get_task(project, title).is_ok() {
+            return Err(crate::error::TmError::DuplicateTask {
+                project: project.to_string(),
+                title: title.to_string(),
+            });
+        }
+        if self.get_archived(project, title).is_ok() {
+            return Err(crate::error::TmError::DuplicateArchivedTask {
+                project: project.to_string(),
+                title: title.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Move a task to another project; its worktree stays where it is
+    pub fn move_task(&mut self, from: &str, title: &str, to: &str) -> TmResult<()> {
+        self.get_task(from, title)?;
+        self.check_title_free(to, title)?;
+
+        let task = self.remove_task(from, title)?;
+        self.add_task(to.to_string(), task)
+    }
+
+    /// Move all active and archived tasks of a project to a new name, merging them into
+    /// the tasks already there. Fails without changes if a title is taken in the target
+    /// Returns the titles of the moved tasks
+    pub fn rename_project(&mut self, old: &str, new: &str) -> TmResult<Vec<String>> {
+        let active = self.projects.get(old).into_iter().flatten();
+        let archived = self
+            .archived
+            .get(old)
+            .into_iter()
+            .flatten()
+            .map(|t| &t.task);
+        let titles: Vec<String> = active.chain(archived).map(|t| t.title.clone()).collect();
+
+        for title in &titles {
+            self.check_title_free(new, title)?;
+        }
+
+        if let Some(tasks) = self.projects.remove(old) {
+            self.projects
+                .entry(new.to_string())
+                .or_default()
+                .extend(tasks);
+        }
+        if let Some(tasks) = self.archived.remove(old) {
+            self.archived
+                .entry(new.to_string())
+                .or_default()
+                .extend(tasks);
+        }
+
+        Ok(titles)
+    }
+
     /// List all tasks, optionally filtered by project
     pub fn list_tasks(&self, project_filter: Option<&str>) -> Vec<(&str, &Task)> {
         let mut result = Vec::new();
@@ -114,4 +294,126 @@ impl TaskStorage {
 
         result
     }
+    /// Add a task to a project's archive
+    /// The archive keeps one entry per title so archived tasks stay selectable
+    pub fn archive_task(&mut self, project: String, archived: ArchivedTask) -> TmResult<()> {
+        let tasks = self.archived.entry(project.clone()).or_default();
+
+        if tasks.iter().any(|t| t.task.title == archived.task.title) {
+            return Err(crate::error::TmError::DuplicateArchivedTask {
+                project,
+                title: archived.task.title.clone(),
+            });
+        }
+
+        tasks.push(archived);
+        Ok(())
+    }
+
+    /// Take a task out of a project's archive
+    pub fn unarchive_task(&mut self, project: &str, title: &str) -> TmResult<ArchivedTask> {
+        let not_found = || crate::error::TmError::TaskNotFound {
+            project: project.to_string(),
+            title: title.to_string(),
+        };
+
+        let tasks = self.archived.get_mut(project).ok_or_else(not_found)?;
+        let index = tasks
+            .iter()
+            .position(|t| t.task.title == title)
+            .ok_or_else(not_found)?;
+
+        let archived = tasks.remove(index);
+
+        // Remove empty projects
+        if tasks.is_empty() {
+            self.archived.remove(project);
+        }
+
+        Ok(archived)
+    }
+
+    /// Get an archived task by project and title
+    pub fn get_archived(&self, project: &str, title: &str) -> TmResult<&ArchivedTask> {
+        self.archived
+            .get(project)
+            .and_then(|tasks| tasks.iter().find(|t| t.task.title == title))
+            .ok_or_else(|| crate::error::TmError::TaskNotFound {
+                project: project.to_string(),
+                title: title.to_string(),
+            })
+    }
+
+    /// List archived tasks, optionally filtered by project
+    pub fn list_archived(&self, project_filter: Option<&str>) -> Vec<(&str, &ArchivedTask)> {
+        self.archived
+            .iter()
+            .filter(|(project, _)| project_filter.is_none_or(|filter| *project == filter))
+            .flat_map(|(project, tasks)| tasks.iter().map(move |t| (project.as_str(), t)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(title: &str) -> Task {
+        Task::new(title.to_string(), PathBuf::from("/tmp").join(title))
+    }
+
+    #[test]
+    fn test_rename_project_merges_or_fails_unchanged() {
+        let mut storage = TaskStorage::new();
+        for (project, title) in [
+            ("a", "feature/J-1-x"),
+            ("a", "feature/J-2-y"),
+            ("b", "feature/J-2-y"),
+        ] {
+            storage.add_task(project.to_string(), task(title)).unwrap();
+        }
+
+        assert!(matches!(
+            storage.rename_project("a", "b"),
+            Err(crate::error::TmError::DuplicateTask { .. })
+        ));
+        assert_eq!(storage.list_tasks(Some("a")).len(), 2);
+
+        storage.move_task("a", "feature/J-2-y", "c").unwrap();
+        assert_eq!(storage.rename_project("a", "b").unwrap(), ["feature/J-1-x"]);
+        assert!(!storage.projects.contains_key("a"));
+        assert_eq!(storage.list_tasks(Some("b")).len(), 2);
+    }
+
+    #[test]
+    fn test_save_rotates_backups() {
+        let dir = std::env::temp_dir().join(format!("tm-storage-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("tasks.toml");
+        let count = |path: &PathBuf| TaskStorage::load(path).unwrap().list_tasks(None).len();
+
+        // Save n holds n tasks, so each backup can be told apart by its task count
+        let mut storage = TaskStorage::new();
+        let saves = BACKUP_COUNT + 2;
+        for n in 1..=saves {
+            storage
+                .add_task("p".to_string(), task(&format!("feature/J-{}-x", n)))
+                .unwrap();
+            storage.save(&path).unwrap();
+        }
+
+        assert_eq!(count(&path), saves);
+        for n in 1..=BACKUP_COUNT {
+            assert_eq!(count(&backup_path(&path, n)), saves - n);
+        }
+        assert!(!backup_path(&path, BACKUP_COUNT + 1).exists());
+        assert!(!with_suffix(&path, ".tmp").exists());
+
+        // Bookkeeping saves leave the backups alone
+        storage.move_task("p", "feature/J-1-x", "q").unwrap();
+        storage.save_without_backup(&path).unwrap();
+        assert_eq!(count(&backup_path(&path, 1)), saves - 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
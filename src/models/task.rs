@@ -1,6 +1,72 @@
+use crate::git::{self, WorkStart};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::path::PathBuf;
 
+/// Lifecycle state of a task, ordered as tasks move through them
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskStatus {
+    /// Not started yet
+    #[default]
+    Todo,
+    /// Actively being worked on
+    InProgress,
+    /// Waiting on review
+    Review,
+    /// Finished
+    Done,
+}
+
+impl TaskStatus {
+    /// Convert to lowercase string for display and storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskStatus::Todo => "todo",
+            TaskStatus::InProgress => "in-progress",
+            TaskStatus::Review => "review",
+            TaskStatus::Done => "done",
+        }
+    }
+
+    /// Check whether moving from this status to `next` is an allowed transition
+    /// todo -> in-progress -> review -> done, with the ability to step back
+    /// (review -> in-progress, in-progress -> todo) and to reopen done tasks
+    pub fn can_transition_to(&self, next: TaskStatus) -> bool {
+        use TaskStatus::*;
+
+        matches!(
+            (self, next),
+            (Todo, InProgress)
+                | (InProgress, Todo)
+                | (InProgress, Review)
+                | (InProgress, Done)
+                | (Review, InProgress)
+                | (Review, Done)
+                | (Done, InProgress)
+        )
+    }
+}
+
+impl fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Task {
     /// Task title - used as unique identifier within a project
@@ -13,6 +79,19 @@ pub struct Task {
     /// Path to the git worktree
     pub worktree_path: PathBuf,
 
+    /// Path to the main repository the worktree belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub main_repo_path: Option<PathBuf>,
+
+    /// Base the task branch was created from (branch, remote branch, tag or SHA)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_branch: Option<String>,
+
+    /// Commit the task's work started from: where its branch forked off the base
+    /// when the task was created, None for tasks stored before it was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_commit: Option<String>,
+
     /// Reference ID for commits (e.g., JIRA-123)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reference: Option<String>,
@@ -24,27 +103,82 @@ pub struct Task {
     /// API URL for future GitHub/Jira integration
     #[serde(skip_serializing_if = "Option::is_none")]
     pub api_url: Option<String>,
+
+    /// Lifecycle state, defaults to todo for tasks stored before statuses existed
+    #[serde(default)]
+    pub status: TaskStatus,
+
+    /// When the task was created, None for tasks stored before timestamps existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+
+    /// When the task's metadata last changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+
+    /// When `tm switch` last went to the task
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_switched_at: Option<DateTime<Utc>>,
 }
 
 impl Task {
     /// Create a new task with required fields
     pub fn new(title: String, worktree_path: PathBuf) -> Self {
+        let now = Utc::now();
         Self {
             title,
             description: None,
             worktree_path,
+            main_repo_path: None,
+            base_branch: None,
+            start_commit: None,
             reference: None,
             remote_url: None,
             api_url: None,
+            status: TaskStatus::default(),
+            created_at: Some(now),
+            updated_at: Some(now),
+            last_switched_at: None,
+        }
+    }
+
+    /// Mark the task's metadata as changed now
+    pub fn touch(&mut self) {
+        self.updated_at = Some(Utc::now());
+    }
+
+    /// How to tell the task's own commits apart from its base's
+    pub fn work_start(&self) -> Option<WorkStart<'_>> {
+        match (&self.start_commit, self.created_at) {
+            (Some(commit), _) => Some(WorkStart::Commit(commit)),
+            (None, Some(created_at)) => Some(WorkStart::Time(created_at.timestamp())),
+            (None, None) => None,
         }
     }
 
+    /// Main repository of the task, found from the worktree for tasks saved without one
+    pub fn main_repo(&self) -> Option<PathBuf> {
+        self.main_repo_path
+            .clone()
+            .or_else(|| git::find_main_repo(&self.worktree_path))
+    }
+
     /// Builder pattern for optional fields
     pub fn with_description(mut self, desc: String) -> Self {
         self.description = Some(desc);
         self
     }
 
+    pub fn with_main_repo_path(mut self, path: PathBuf) -> Self {
+        self.main_repo_path = Some(path);
+        self
+    }
+
+    pub fn with_base_branch(mut self, base: String) -> Self {
+        self.base_branch = Some(base);
+        self
+    }
+
     pub fn with_reference(mut self, reference: String) -> Self {
         self.reference = Some(reference);
         self
@@ -60,3 +194,69 @@ impl Task {
         self
     }
 }
+
+/// A task moved out of the active list by `tm archive`, with where its work ended
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ArchivedTask {
+    #[serde(flatten)]
+    pub task: Task,
+
+    /// When the task was archived
+    pub archived_at: DateTime<Utc>,
+
+    /// Branch checked out in the worktree when archived, None if HEAD was detached
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+
+    /// Commit checked out in the worktree when archived
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_transitions() {
+        assert!(TaskStatus::Todo.can_transition_to(TaskStatus::InProgress));
+        assert!(TaskStatus::InProgress.can_transition_to(TaskStatus::Review));
+        assert!(TaskStatus::Review.can_transition_to(TaskStatus::Done));
+        assert!(TaskStatus::Done.can_transition_to(TaskStatus::InProgress));
+        assert!(!TaskStatus::Todo.can_transition_to(TaskStatus::Done));
+        assert!(!TaskStatus::Todo.can_transition_to(TaskStatus::Todo));
+        assert!(!TaskStatus::Done.can_transition_to(TaskStatus::Review));
+    }
+
+    #[test]
+    fn test_status_defaults_for_old_tasks() {
+        let task: Task = toml::from_str(
+            r#"
+            title = "feature/JIRA-1-auth"
+            worktree_path = "/tmp/feature/JIRA-1-auth"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(task.status, TaskStatus::Todo);
+        assert_eq!(task.created_at, None);
+    }
+
+    #[test]
+    fn test_archived_task_roundtrip() {
+        let archived = ArchivedTask {
+            task: Task::new(
+                "feature/JIRA-1-auth".to_string(),
+                PathBuf::from("/tmp/feature/JIRA-1-auth"),
+            )
+            .with_reference("JIRA-1".to_string()),
+            archived_at: "2024-05-01T12:00:00Z".parse().unwrap(),
+            branch: Some("feature/JIRA-1-auth".to_string()),
+            commit: None,
+        };
+
+        let content = toml::to_string(&archived).unwrap();
+        assert!(content.contains("archived_at = \"2024-05-01T12:00:00Z\""));
+        assert_eq!(toml::from_str::<ArchivedTask>(&content).unwrap(), archived);
+    }
+}
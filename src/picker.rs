@@ -0,0 +1,174 @@
+use crate::error::TmResult;
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::io::{stderr, Stderr, Write};
+
+/// Puts the terminal back into its normal state, also when the picker fails
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> TmResult<Self> {
+        terminal::enable_raw_mode()?;
+        let guard = Self;
+        execute!(stderr(), EnterAlternateScreen, Hide)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(stderr(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// An item matching the query, with the positions of the matched characters
+struct Match {
+    index: usize,
+    positions: Vec<usize>,
+}
+
+/// Items matching `query`, best first; all items in their order for an empty query
+fn filter(matcher: &SkimMatcherV2, items: &[String], query: &str) -> Vec<Match> {
+    if query.is_empty() {
+        return (0..items.len())
+            .map(|index| Match {
+                index,
+                positions: Vec::new(),
+            })
+            .collect();
+    }
+
+    let mut matches: Vec<(i64, Match)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| {
+            matcher
+                .fuzzy_indices(item, query)
+                .map(|(score, positions)| (score, Match { index, positions }))
+        })
+        .collect();
+    matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    matches.into_iter().map(|(_, m)| m).collect()
+}
+
+fn draw(
+    out: &mut Stderr,
+    prompt: &str,
+    query: &str,
+    items: &[String],
+    matches: &[Match],
+    selected: usize,
+    offset: usize,
+) -> TmResult<()> {
+    let (width, height) = terminal::size()?;
+    let width = width as usize;
+
+    queue!(
+        out,
+        MoveTo(0, 0),
+        Clear(ClearType::All),
+        Print(format!("{}> {}", prompt, query)),
+        MoveTo(0, 1),
+        SetAttribute(Attribute::Dim),
+        Print(format!("  {}/{}", matches.len(), items.len())),
+        SetAttribute(Attribute::Reset),
+    )?;
+
+    let rows = (height as usize).saturating_sub(2);
+    for (row, m) in matches.iter().skip(offset).take(rows).enumerate() {
+        let is_selected = offset + row == selected;
+        queue!(out, MoveTo(0, row as u16 + 2))?;
+        if is_selected {
+            queue!(out, SetAttribute(Attribute::Reverse), Print("> "))?;
+        } else {
+            queue!(out, Print("  "))?;
+        }
+
+        // Highlight matched characters, truncating to the terminal width
+        for (i, ch) in items[m.index]
+            .chars()
+            .take(width.saturating_sub(2))
+            .enumerate()
+        {
+            if m.positions.contains(&i) {
+                queue!(
+                    out,
+                    SetAttribute(Attribute::Bold),
+                    SetAttribute(Attribute::Underlined),
+                    Print(ch),
+                    SetAttribute(Attribute::NoBold),
+                    SetAttribute(Attribute::NoUnderline),
+                )?;
+            } else {
+                queue!(out, Print(ch))?;
+            }
+        }
+        queue!(out, SetAttribute(Attribute::Reset))?;
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+/// Let the user pick one of `items` by typing a fuzzy query
+/// Draws on stderr so stdout stays free for the result; returns None if cancelled
+pub fn pick(prompt: &str, items: &[String]) -> TmResult<Option<usize>> {
+    let matcher = SkimMatcherV2::default();
+    let mut out = stderr();
+    let _guard = TerminalGuard::enter()?;
+
+    let mut query = String::new();
+    let mut selected = 0;
+    let mut offset = 0;
+
+    loop {
+        let matches = filter(&matcher, items, &query);
+        selected = selected.min(matches.len().saturating_sub(1));
+
+        // Scroll so the selected row stays visible
+        let rows = (terminal::size()?.1 as usize).saturating_sub(2).max(1);
+        if selected < offset {
+            offset = selected;
+        } else if selected >= offset + rows {
+            offset = selected + 1 - rows;
+        }
+
+        draw(&mut out, prompt, &query, items, &matches, selected, offset)?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return Ok(None),
+            KeyCode::Char('c') | KeyCode::Char('d') if ctrl => return Ok(None),
+            KeyCode::Enter => return Ok(matches.get(selected).map(|m| m.index)),
+            KeyCode::Up => selected = selected.saturating_sub(1),
+            KeyCode::Char('p') | KeyCode::Char('k') if ctrl => {
+                selected = selected.saturating_sub(1)
+            }
+            KeyCode::Down => selected += 1,
+            KeyCode::Char('n') | KeyCode::Char('j') if ctrl => selected += 1,
+            KeyCode::Char('u') if ctrl => query.clear(),
+            KeyCode::Backspace => {
+                query.pop();
+            }
+            KeyCode::Char(c) if !ctrl => {
+                query.push(c);
+                selected = 0;
+                offset = 0;
+            }
+            _ => {}
+        }
+    }
+}
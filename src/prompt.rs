@@ -0,0 +1,17 @@
+use crate::error::TmResult;
+use std::io::Write;
+
+/// Ask a yes/no question on stderr and read the answer from stdin
+/// An empty answer, or end of input, picks `default`
+pub fn confirm(question: &str, default: bool) -> TmResult<bool> {
+    let hint = if default { "[Y/n]" } else { "[y/N]" };
+    eprint!("{} {} ", question, hint);
+    std::io::stderr().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(match answer.trim().to_lowercase().as_str() {
+        "" => default,
+        answer => matches!(answer, "y" | "yes"),
+    })
+}
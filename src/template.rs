@@ -0,0 +1,240 @@
+use crate::git::{to_kebab_case, to_snake_case};
+use std::path::{Component, Path};
+
+/// Default branch name template, `<level>/<id>-<name_kebab_case>`
+pub const DEFAULT_BRANCH_TEMPLATE: &str = "{level}/{id}-{name:kebab}";
+
+/// Default worktree path template relative to the worktree root,
+/// `<level>/<id>-<name_snake_case>`
+pub const DEFAULT_WORKTREE_TEMPLATE: &str = "{level}/{id}-{name:snake}";
+
+/// Values substituted into templates
+pub struct TemplateVars<'a> {
+    pub user: &'a str,
+    pub project: &'a str,
+    pub level: &'a str,
+    pub id: &'a str,
+    pub name: &'a str,
+}
+
+impl TemplateVars<'static> {
+    /// Representative values used to validate templates at load time
+    fn sample() -> Self {
+        Self {
+            user: "user",
+            project: "project",
+            level: "feature",
+            id: "ABC-123",
+            name: "Sample name",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Var {
+    User,
+    Project,
+    Level,
+    Id,
+    Name,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Case {
+    Raw,
+    Kebab,
+    Snake,
+    Lower,
+    Upper,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Placeholder(Var, Case),
+}
+
+/// A parsed template such as `{user}/{level}/{id}-{name:kebab}`
+/// Placeholders: user, project, level, id, name, each optionally followed by
+/// `:kebab`, `:snake`, `:lower` or `:upper`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    /// Parse a template string, rejecting unknown placeholders and case modifiers
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut rest = template;
+
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                segments.push(Segment::Literal(rest[..start].to_string()));
+            }
+
+            let end = rest[start..]
+                .find('}')
+                .map(|i| start + i)
+                .ok_or_else(|| format!("unclosed '{{' in '{}'", template))?;
+            let spec = &rest[start + 1..end];
+            let (var, case) = spec.split_once(':').unwrap_or((spec, ""));
+
+            let var = match var {
+                "user" => Var::User,
+                "project" => Var::Project,
+                "level" => Var::Level,
+                "id" => Var::Id,
+                "name" => Var::Name,
+                _ => return Err(format!("unknown placeholder '{{{}}}'", spec)),
+            };
+            let case = match case {
+                "" => Case::Raw,
+                "kebab" => Case::Kebab,
+                "snake" => Case::Snake,
+                "lower" => Case::Lower,
+                "upper" => Case::Upper,
+                _ => return Err(format!("unknown case '{}' in '{{{}}}'", case, spec)),
+            };
+
+            segments.push(Segment::Placeholder(var, case));
+            rest = &rest[end + 1..];
+        }
+
+        if !rest.is_empty() {
+            segments.push(Segment::Literal(rest.to_string()));
+        }
+
+        Ok(Self {
+            source: template.to_string(),
+            segments,
+        })
+    }
+
+    /// Parse and check a branch name template against git's ref-name rules
+    pub fn parse_branch(template: &str) -> Result<Self, String> {
+        let parsed = Self::parse(template)?;
+        let sample = parsed.render(&TemplateVars::sample());
+
+        if !git2::Reference::is_valid_name(&format!("refs/heads/{}", sample)) {
+            return Err(format!(
+                "'{}' does not produce a valid branch name (e.g. '{}')",
+                template, sample
+            ));
+        }
+
+        Ok(parsed)
+    }
+
+    /// Parse and check a worktree path template, which must stay inside the worktree root
+    pub fn parse_worktree(template: &str) -> Result<Self, String> {
+        let parsed = Self::parse(template)?;
+        let sample = parsed.render(&TemplateVars::sample());
+
+        let escapes_root = Path::new(&sample)
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_)));
+        if sample.is_empty() || escapes_root {
+            return Err(format!(
+                "'{}' must be a relative path inside the worktree root (e.g. '{}')",
+                template, sample
+            ));
+        }
+
+        Ok(parsed)
+    }
+
+    /// Substitute the placeholders
+    pub fn render(&self, vars: &TemplateVars) -> String {
+        let mut result = String::new();
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => result.push_str(text),
+                Segment::Placeholder(var, case) => {
+                    let value = match var {
+                        Var::User => vars.user,
+                        Var::Project => vars.project,
+                        Var::Level => vars.level,
+                        Var::Id => vars.id,
+                        Var::Name => vars.name,
+                    };
+                    let value = match case {
+                        Case::Raw => value.to_string(),
+                        Case::Kebab => to_kebab_case(value),
+                        Case::Snake => to_snake_case(value),
+                        Case::Lower => value.to_lowercase(),
+                        Case::Upper => value.to_uppercase(),
+                    };
+                    result.push_str(&value);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// The template as written
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::generate_branch_name;
+
+    fn vars<'a>(level: &'a str, id: &'a str, name: &'a str) -> TemplateVars<'a> {
+        TemplateVars {
+            user: "jdoe",
+            project: "myapp",
+            level,
+            id,
+            name,
+        }
+    }
+
+    #[test]
+    fn test_default_templates_match_existing_scheme() {
+        let branch = Template::parse_branch(DEFAULT_BRANCH_TEMPLATE).unwrap();
+        let worktree = Template::parse_worktree(DEFAULT_WORKTREE_TEMPLATE).unwrap();
+
+        assert_eq!(
+            branch.render(&vars("feature", "JIRA-123", "Auth System")),
+            generate_branch_name("feature", "JIRA-123", "Auth System")
+        );
+        assert_eq!(
+            worktree.render(&vars("feature", "JIRA-123", "Auth System")),
+            "feature/JIRA-123-auth_system"
+        );
+    }
+
+    #[test]
+    fn test_custom_templates() {
+        let branch = Template::parse_branch("users/{user}/{id:lower}").unwrap();
+        assert_eq!(
+            branch.render(&vars("fix", "BUG-7", "crash")),
+            "users/jdoe/bug-7"
+        );
+
+        let worktree = Template::parse_worktree("{project}-{id}").unwrap();
+        assert_eq!(
+            worktree.render(&vars("fix", "BUG-7", "crash")),
+            "myapp-BUG-7"
+        );
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        assert!(Template::parse("{level}/{title}").is_err());
+        assert!(Template::parse("{name:camel}").is_err());
+        assert!(Template::parse("{level").is_err());
+        assert!(Template::parse_branch("{level}..{id}").is_err());
+        assert!(Template::parse_branch("{id} {name}").is_err());
+        assert!(Template::parse_branch("{level}/").is_err());
+        assert!(Template::parse_worktree("../{id}").is_err());
+        assert!(Template::parse_worktree("/tmp/{id}").is_err());
+    }
+}